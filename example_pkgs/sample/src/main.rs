@@ -53,7 +53,7 @@ where
         .axis_desc_style(("serif", 15))
         .x_desc("Time")
         .y_desc("Value")
-        .light_line_style(&WHITE)
+        .light_line_style(WHITE)
         .draw()?;
 
     Ok(ret)
@@ -35,7 +35,7 @@ impl NdeqNet<f32> for Net {
     fn edges(&self) -> Box<dyn Iterator<Item = (usize, usize, f32)> + '_> {
         let nodes = RefIter::new(self.nodes.borrow(), |x| x.iter());
         let ret = nodes.iflat_map(Self::node_edges);
-        return Box::new(ret);
+        Box::new(ret)
     }
 
     fn export_values(&self, values: &mut Vec<f32>) {
@@ -46,6 +46,17 @@ impl NdeqNet<f32> for Net {
         }
     }
 
+    fn export_ids(&self, ids: &mut Vec<usize>) {
+        ids.clear();
+        for node in self.nodes.borrow().iter() {
+            ids.push(node.id());
+        }
+    }
+
+    fn nodes_count(&self) -> usize {
+        self.nodes.borrow().len()
+    }
+
     fn import_values(&self, values: &[f32]) {
         assert_eq!(values.len(), self.nodes.borrow().len());
 
@@ -4,10 +4,14 @@ use ndeq::prelude::*;
 use ref_iter::prelude::*;
 use std::cell::RefCell;
 use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
 
 pub struct Node {
     net: Nw<Net>,
     this: Nw<Self>,
+    id: usize,
     work_idx: RefCell<usize>,
     value: RefCell<f32>,
     edges: RefCell<BTreeMap<Nw<Self>, f32>>,
@@ -18,6 +22,7 @@ impl Node {
         Nr::new_cyclic(|this| Self {
             net,
             this: this.clone(),
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
             work_idx: Default::default(),
             value: Default::default(),
             edges: Default::default(),
@@ -29,10 +34,14 @@ impl Node {
         assert!(self.net == node.net);
         let mut self_edges = self.edges.borrow_mut();
         let mut node_edges = node.edges.borrow_mut();
-        self_edges.insert(Nr::downgrade(&node), w);
+        self_edges.insert(Nr::downgrade(node), w);
         node_edges.insert(Nr::downgrade(&self.this()), w);
     }
 
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
     fn this(&self) -> Nr<Self> {
         self.this.upgrade().unwrap()
     }
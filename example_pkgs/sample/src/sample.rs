@@ -41,9 +41,9 @@ impl Sample {
     }
 
     pub fn run_simulation(&mut self) {
-        let net = &*self.net as &dyn NdeqNet<f32>;
+        let net = CompiledNet::new(&*self.net as &dyn NdeqNet<f32>);
         let solver = NetEuler::new(H);
-        let mut sim = NdeqSim::new(net, &solver);
+        let mut sim = NdeqSim::new(&net, &solver);
 
         let mut t = T_RANGE.start;
         while t <= T_RANGE.end {
@@ -0,0 +1,120 @@
+//! Derive macros for ndeq.
+//!
+//! *The author of this crate is not good at English.*
+//! *Forgive me if the document is hard to read.*
+
+#![warn(missing_docs)]
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, Index, parse_macro_input, parse_quote};
+
+/// Derives field-wise arithmetic operators required by `Value`.
+///
+/// This implements `MulAssign<U>` and `DivAssign<U>` (for any `U` supported
+/// by all fields, like `RF32`, `RF64` and time type), `AddAssign<&Self>` and
+/// `SubAssign<&Self>`. Fields must be values themselves. `Clone`, `Default`
+/// and `PartialEq` are not derived by this (derive them as usual).
+///
+/// # Examples
+///
+/// ```ignore
+/// #[derive(Clone, Default, PartialEq, Value)]
+/// struct Weather {
+///     temperature: f64,
+///     humidity: f64,
+///     pollutant: f64,
+/// }
+/// ```
+#[proc_macro_derive(Value)]
+pub fn derive_value(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(x) => x.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// Expands derive input.
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(input, msg::NOT_STRUCT));
+    };
+
+    let members = match &data.fields {
+        Fields::Named(fields) => fields
+            .named
+            .iter()
+            .map(|f| {
+                let ident = f.ident.as_ref().unwrap();
+                quote!(#ident)
+            })
+            .collect::<Vec<_>>(),
+        Fields::Unnamed(fields) => (0..fields.unnamed.len())
+            .map(|i| {
+                let index = Index::from(i);
+                quote!(#index)
+            })
+            .collect(),
+        Fields::Unit => Vec::new(),
+    };
+
+    let types = data.fields.iter().map(|f| &f.ty).collect::<Vec<_>>();
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let mut scaled = input.generics.clone();
+    scaled.params.push(parse_quote!(__U));
+    let (scaled_generics, _, _) = scaled.split_for_impl();
+    let preds = where_clause.map(|x| x.predicates.iter().collect::<Vec<_>>());
+    let preds = preds.unwrap_or_default();
+    let ops = quote!(::core::ops);
+
+    Ok(quote! {
+        impl #scaled_generics #ops::MulAssign<__U> for #name #ty_generics
+        where
+            __U: ::core::marker::Copy,
+            #(#types: #ops::MulAssign<__U>,)*
+            #(#preds,)*
+        {
+            fn mul_assign(&mut self, rhs: __U) {
+                #(self.#members *= rhs;)*
+            }
+        }
+
+        impl #scaled_generics #ops::DivAssign<__U> for #name #ty_generics
+        where
+            __U: ::core::marker::Copy,
+            #(#types: #ops::DivAssign<__U>,)*
+            #(#preds,)*
+        {
+            fn div_assign(&mut self, rhs: __U) {
+                #(self.#members /= rhs;)*
+            }
+        }
+
+        impl #impl_generics #ops::AddAssign<&Self> for #name #ty_generics
+        where
+            #(#types: for<'__a> #ops::AddAssign<&'__a #types>,)*
+            #(#preds,)*
+        {
+            fn add_assign(&mut self, rhs: &Self) {
+                #(self.#members += &rhs.#members;)*
+            }
+        }
+
+        impl #impl_generics #ops::SubAssign<&Self> for #name #ty_generics
+        where
+            #(#types: for<'__a> #ops::SubAssign<&'__a #types>,)*
+            #(#preds,)*
+        {
+            fn sub_assign(&mut self, rhs: &Self) {
+                #(self.#members -= &rhs.#members;)*
+            }
+        }
+    })
+}
+
+mod msg {
+    pub const NOT_STRUCT: &str = "`Value` can be derived only for structs.";
+}
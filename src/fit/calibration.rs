@@ -0,0 +1,431 @@
+//! Provider of [`Calibration`].
+
+use crate::fit::{FitReport, Observation};
+use crate::net_ode::NdeqSim;
+use crate::net_ode::solver::adapters::NetRungeKutta;
+use crate::ode::values::Dual;
+use crate::parts::{Laplacian, NdeqNet, Weight};
+use crate::util::NetCopy;
+
+/// Edge weight calibration from observed node values.
+///
+/// This estimates edge weights of network by nonlinear least squares of
+/// residuals between simulated and observed node values. Minimization is
+/// done by [Levenberg-Marquardt algorithm], and weights are projected into
+/// bounds after each step (weights on bounds are held if gradient pushes
+/// them outward). Jacobian of residuals is calculated by forward sensitivity
+/// of simulator (see [`NdeqSim::set_sensitivity`]) with the fourth order
+/// Runge-Kutta method.
+///
+/// Initial guess is current edge weights of target network. Node values
+/// at the start of simulation are current node values of target network.
+/// Topology, edge mode, fixed nodes, capacities, constant sources and
+/// advection edges of target network are used by simulation.
+///
+/// [Levenberg-Marquardt algorithm]: https://en.wikipedia.org/wiki/Levenberg%E2%80%93Marquardt_algorithm
+pub struct Calibration<'a, W = f32> {
+    /// Target network.
+    net: &'a dyn NdeqNet<f64, W>,
+
+    /// Step size of simulation.
+    h: f64,
+
+    /// Bounds of edge weights (empty means `[0, inf)` for all edges).
+    bounds: Vec<(f64, f64)>,
+
+    /// Maximum iterations count.
+    max_iters: usize,
+
+    /// Relative tolerance of convergence.
+    tol: f64,
+}
+
+impl<'a, W> Calibration<'a, W>
+where
+    W: Weight,
+{
+    /// Creates a new instance.
+    ///
+    /// Bounds of all edge weights are `[0, inf)`, maximum iterations count is
+    /// `100` and tolerance is `1e-10` by default.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `h` is zero or negative or NaN or infinity.
+    pub fn new(net: &'a dyn NdeqNet<f64, W>, h: f64) -> Self {
+        assert!(h.is_finite() && h > 0.0, "{}", msg::INVALID_STEP);
+        Self {
+            net,
+            h,
+            bounds: Vec::new(),
+            max_iters: 100,
+            tol: 1e-10,
+        }
+    }
+
+    /// Sets bounds (lower and upper) of edge weights.
+    ///
+    /// Items are same order as [`NdeqNet::edges`]. Bounds can be infinite.
+    ///
+    /// # Panics
+    ///
+    /// Panics if lower bound is greater than upper bound, or either is NaN.
+    pub fn set_bounds(&mut self, bounds: Vec<(f64, f64)>) {
+        for &(lower, upper) in &bounds {
+            assert!(lower <= upper, "{}", msg::INVALID_BOUNDS);
+        }
+
+        self.bounds = bounds;
+    }
+
+    /// Sets maximum iterations count.
+    pub fn set_max_iters(&mut self, value: usize) {
+        self.max_iters = value;
+    }
+
+    /// Sets relative tolerance of convergence.
+    ///
+    /// Iteration stops when relative change of weights or relative decrease
+    /// of cost becomes less than this, or when norm of `J^T * r` (gradient
+    /// of cost projected to bounds, `J` is Jacobian of residuals `r`)
+    /// becomes less than this. If cost cannot be decreased by any damping,
+    /// iteration stops without convergence.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` is negative or NaN.
+    pub fn set_tolerance(&mut self, value: f64) {
+        assert!(value >= 0.0, "{}", msg::INVALID_TOLERANCE);
+        self.tol = value;
+    }
+
+    /// Estimates edge weights from observations.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the following occurs.
+    ///
+    /// * Observations are empty.
+    /// * Bounds count is not equal to edges count.
+    /// * Observation has node index out of nodes count, or negative time.
+    /// * Target network is time-varying or its Laplacian is not
+    ///   [`Laplacian::Combinatorial`].
+    /// * Target network or its nodes are currently mutably borrowed.
+    pub fn fit(&self, observations: &[Observation]) -> FitReport {
+        assert!(!observations.is_empty(), "{}", msg::NO_OBSERVATIONS);
+        assert!(!self.net.is_time_varying(), "{}", msg::TIME_VARYING);
+        assert!(
+            self.net.laplacian() == Laplacian::Combinatorial,
+            "{}",
+            msg::LAPLACIAN
+        );
+
+        let model = NetCopy::<D>::new(self.net);
+        let bounds = self.bounds(model.edge_nodes().len());
+        let clamp = |x: &mut [f64]| {
+            for (x, &(lower, upper)) in x.iter_mut().zip(&bounds) {
+                *x = x.clamp(lower, upper);
+            }
+        };
+
+        for obs in observations {
+            assert!(obs.idx < model.initial().len(), "{}", msg::IDX_OUT_OF_RANGE);
+            assert!(obs.time >= 0.0, "{}", msg::INVALID_TIME);
+        }
+
+        let mut order = (0..observations.len()).collect::<Vec<_>>();
+        order.sort_by(|&a, &b| observations[a].time.total_cmp(&observations[b].time));
+        let eval = |x: &[f64], jacobian: Option<&mut Vec<Vec<f64>>>| {
+            residuals(&model, self.h, x, observations, &order, jacobian)
+        };
+
+        let mut x = model.weights();
+        clamp(&mut x);
+
+        let mut jacobian = Vec::new();
+        let mut r = eval(&x, Some(&mut jacobian));
+        let mut cost = sum_sqr(&r);
+        let mut lambda = 1e-3;
+        let mut iterations = 0;
+        let mut converged = false;
+        'outer: while iterations < self.max_iters {
+            let (mut a, mut g) = normal_equations(&jacobian, &r);
+            for (i, &(lower, upper)) in bounds.iter().enumerate() {
+                if (x[i] <= lower && g[i] > 0.0) || (x[i] >= upper && g[i] < 0.0) {
+                    a.iter_mut().for_each(|row| row[i] = 0.0);
+                    a[i].fill(0.0);
+                    a[i][i] = 1.0;
+                    g[i] = 0.0;
+                }
+            }
+
+            if sum_sqr(&g).sqrt() <= self.tol {
+                converged = true;
+                break;
+            }
+
+            iterations += 1;
+            loop {
+                let mut damped = a.clone();
+                for (i, row) in damped.iter_mut().enumerate() {
+                    row[i] += lambda * a[i][i].max(f64::MIN_POSITIVE);
+                }
+
+                let step = cholesky(&damped).map(|l| solve(&l, &g));
+                let mut x_new = x.clone();
+                if let Some(step) = &step {
+                    x_new.iter_mut().zip(step).for_each(|(x, d)| *x -= d);
+                    clamp(&mut x_new);
+                }
+
+                let r_new = eval(&x_new, None);
+                let cost_new = sum_sqr(&r_new);
+                if step.is_some() && cost_new < cost {
+                    let change = sum_sqr(&diff(&x_new, &x)).sqrt();
+                    let scale = sum_sqr(&x).sqrt();
+                    let decrease = cost - cost_new;
+                    x = x_new;
+                    r = eval(&x, Some(&mut jacobian));
+                    cost = sum_sqr(&r);
+                    lambda = (lambda / 10.0).max(1e-12);
+                    if change <= self.tol * (scale + self.tol) || decrease <= self.tol * cost {
+                        converged = true;
+                        break 'outer;
+                    }
+
+                    break;
+                }
+
+                lambda *= 10.0;
+                if lambda > 1e12 {
+                    break 'outer;
+                }
+            }
+        }
+
+        let covariance = covariance(&jacobian, cost, observations.len());
+        let std_errors = (0..x.len()).map(|i| covariance[i][i].sqrt()).collect();
+        FitReport {
+            weights: x,
+            residuals: r,
+            cost,
+            covariance,
+            std_errors,
+            iterations,
+            converged,
+        }
+    }
+
+    /// Returns bounds of edge weights.
+    fn bounds(&self, len: usize) -> Vec<(f64, f64)> {
+        if self.bounds.is_empty() {
+            return vec![(0.0, f64::INFINITY); len];
+        }
+
+        assert_eq!(self.bounds.len(), len, "{}", msg::BOUNDS_MISSMATCH);
+        self.bounds.clone()
+    }
+}
+
+/// Tangent components count of model node values.
+const BATCH: usize = 8;
+
+/// Model node value.
+type D = Dual<f64, BATCH>;
+
+/// Returns residuals with weights `x` (and writes Jacobian if needed).
+fn residuals(
+    model: &NetCopy<D>,
+    h: f64,
+    x: &[f64],
+    observations: &[Observation],
+    order: &[usize],
+    mut jacobian: Option<&mut Vec<Vec<f64>>>,
+) -> Vec<f64> {
+    let mut ret = vec![0.0; observations.len()];
+    let batches = match &mut jacobian {
+        None => 1,
+        Some(jacobian) => {
+            jacobian.clear();
+            jacobian.resize(observations.len(), vec![0.0; x.len()]);
+            x.len().div_ceil(BATCH).max(1)
+        }
+    };
+
+    model.set_weights(x);
+    let solver = NetRungeKutta::new(h);
+    for batch in 0..batches {
+        let start = batch * BATCH;
+        let end = (start + BATCH).min(x.len());
+        model.reset_values();
+
+        let mut sim = NdeqSim::new(model, &solver);
+        if jacobian.is_some() {
+            sim.set_sensitivity(model.edge_nodes()[start..end].to_vec());
+        }
+
+        for &j in order {
+            let obs = &observations[j];
+            if obs.time > sim.time() {
+                sim.run(obs.time - sim.time());
+            }
+
+            let value = model.value(obs.idx);
+            ret[j] = value.re - obs.value;
+            if let Some(jacobian) = &mut jacobian {
+                jacobian[j][start..end].copy_from_slice(&value.eps[..end - start]);
+            }
+        }
+    }
+
+    ret
+}
+
+/// Returns `J^T * J` and `J^T * r`.
+fn normal_equations(jacobian: &[Vec<f64>], r: &[f64]) -> (Vec<Vec<f64>>, Vec<f64>) {
+    let len = jacobian.first().map_or(0, |x| x.len());
+    let mut a = vec![vec![0.0; len]; len];
+    let mut g = vec![0.0; len];
+    for (row, &r) in jacobian.iter().zip(r) {
+        for i in 0..len {
+            g[i] += row[i] * r;
+            for j in 0..len {
+                a[i][j] += row[i] * row[j];
+            }
+        }
+    }
+
+    (a, g)
+}
+
+/// Returns covariance matrix of weights (NaN if it is not determined).
+fn covariance(jacobian: &[Vec<f64>], cost: f64, len: usize) -> Vec<Vec<f64>> {
+    let (a, _) = normal_equations(jacobian, &vec![0.0; jacobian.len()]);
+    let p = a.len();
+    let mut ret = vec![vec![f64::NAN; p]; p];
+    let Some(l) = cholesky(&a).filter(|_| len > p) else {
+        return ret;
+    };
+
+    let s2 = cost / (len - p) as f64;
+    for j in 0..p {
+        let mut e = vec![0.0; p];
+        e[j] = 1.0;
+        let col = solve(&l, &e);
+        for i in 0..p {
+            ret[i][j] = s2 * col[i];
+        }
+    }
+
+    ret
+}
+
+/// Returns lower triangular Cholesky factor (`None` if not positive definite).
+fn cholesky(a: &[Vec<f64>]) -> Option<Vec<Vec<f64>>> {
+    let len = a.len();
+    let mut l = vec![vec![0.0; len]; len];
+    for i in 0..len {
+        for j in 0..=i {
+            let sum = (0..j).map(|k| l[i][k] * l[j][k]).sum::<f64>();
+            if i == j {
+                let d = a[i][i] - sum;
+                if d.is_nan() || d <= 0.0 {
+                    return None;
+                }
+
+                l[i][i] = d.sqrt();
+            } else {
+                l[i][j] = (a[i][j] - sum) / l[j][j];
+            }
+        }
+    }
+
+    Some(l)
+}
+
+/// Solves `L * L^T * x = b`.
+fn solve(l: &[Vec<f64>], b: &[f64]) -> Vec<f64> {
+    let len = b.len();
+    let mut y = b.to_vec();
+    for i in 0..len {
+        let sum = (0..i).map(|k| l[i][k] * y[k]).sum::<f64>();
+        y[i] = (y[i] - sum) / l[i][i];
+    }
+
+    for i in (0..len).rev() {
+        let sum = (i + 1..len).map(|k| l[k][i] * y[k]).sum::<f64>();
+        y[i] = (y[i] - sum) / l[i][i];
+    }
+
+    y
+}
+
+/// Returns sum of squares.
+fn sum_sqr(x: &[f64]) -> f64 {
+    x.iter().map(|x| x * x).sum()
+}
+
+/// Returns `x - y`.
+fn diff(x: &[f64], y: &[f64]) -> Vec<f64> {
+    x.iter().zip(y).map(|(x, y)| x - y).collect()
+}
+
+mod msg {
+    pub const INVALID_STEP: &str = "Step size must be positive and finite.";
+    pub const INVALID_TOLERANCE: &str = "Tolerance must be zero or positive.";
+    pub const NO_OBSERVATIONS: &str = "Observations must not be empty.";
+    pub const INVALID_TIME: &str = "Observation time must be non-negative.";
+    pub const IDX_OUT_OF_RANGE: &str = "Observation node index is out of nodes count.";
+    pub const BOUNDS_MISSMATCH: &str = "Bounds and edges count missmatch.";
+    pub const INVALID_BOUNDS: &str = "Lower bound must not be greater than upper bound.";
+    pub const TIME_VARYING: &str = "Calibration does not support time-varying network.";
+    pub const LAPLACIAN: &str = "Calibration supports only combinatorial Laplacian.";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{PATH_VALUES, PATH_WEIGHTS, TestNet};
+
+    #[test]
+    fn fit_recovers_weights() {
+        let net = TestNet::path(PATH_VALUES.to_vec(), PATH_WEIGHTS);
+        let solver = NetRungeKutta::new(0.01);
+        let mut sim = NdeqSim::new(&net, &solver);
+        let mut observations = Vec::new();
+        for _ in 0..10 {
+            sim.run(0.1);
+            let time = sim.time();
+            let values = net.values();
+            observations.extend((0..3).map(|idx| Observation::new(idx, time, values[idx])));
+        }
+
+        let net = TestNet::path(PATH_VALUES.to_vec(), [1.0, 1.0]);
+        let report = Calibration::new(&net, 0.01).fit(&observations);
+        assert!(report.converged);
+        for (weight, expected) in report.weights.iter().zip(PATH_WEIGHTS) {
+            assert!((weight - expected).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Observations must not be empty.")]
+    fn fit_rejects_empty_observations() {
+        let net = TestNet::path(PATH_VALUES.to_vec(), PATH_WEIGHTS);
+        Calibration::new(&net, 0.01).fit(&[]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Tolerance must be zero or positive.")]
+    fn set_tolerance_rejects_negative() {
+        let net = TestNet::path(PATH_VALUES.to_vec(), PATH_WEIGHTS);
+        Calibration::new(&net, 0.01).set_tolerance(-1.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Lower bound must not be greater than upper bound.")]
+    fn set_bounds_rejects_inverted_bounds() {
+        let net = TestNet::new(vec![1.0, 0.0], vec![(0, 1, 1.0)]);
+        Calibration::new(&net, 0.01).set_bounds(vec![(1.0, 0.0)]);
+    }
+}
@@ -0,0 +1,32 @@
+//! Provider of [`FitReport`].
+
+/// Result of edge weight calibration.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FitReport {
+    /// Estimated edge weights (same order as [`NdeqNet::edges`]).
+    ///
+    /// [`NdeqNet::edges`]: crate::parts::NdeqNet::edges
+    pub weights: Vec<f64>,
+
+    /// Residuals (model value minus observed value) of observations.
+    pub residuals: Vec<f64>,
+
+    /// Sum of squared residuals.
+    pub cost: f64,
+
+    /// Covariance matrix of estimated weights (row-major).
+    ///
+    /// This is `s^2 * (J^T * J)^-1` (`s^2` is residual variance and `J` is
+    /// Jacobian of residuals). Items are NaN if weights are not identifiable
+    /// from observations.
+    pub covariance: Vec<Vec<f64>>,
+
+    /// Standard errors of estimated weights.
+    pub std_errors: Vec<f64>,
+
+    /// Iterations count.
+    pub iterations: usize,
+
+    /// `true` if iteration converged before limit.
+    pub converged: bool,
+}
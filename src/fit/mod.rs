@@ -0,0 +1,9 @@
+//! Edge weight calibration.
+
+pub use calibration::*;
+pub use fit_report::*;
+pub use observation::*;
+
+mod calibration;
+mod fit_report;
+mod observation;
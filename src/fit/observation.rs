@@ -0,0 +1,21 @@
+//! Provider of [`Observation`].
+
+/// Observed node value.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Observation {
+    /// Node index.
+    pub idx: usize,
+
+    /// Elapsed time from the start of simulation.
+    pub time: f64,
+
+    /// Observed value.
+    pub value: f64,
+}
+
+impl Observation {
+    /// Creates a new instance.
+    pub fn new(idx: usize, time: f64, value: f64) -> Self {
+        Self { idx, time, value }
+    }
+}
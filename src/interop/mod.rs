@@ -0,0 +1,8 @@
+//! Interoperability with other crates.
+//!
+//! Modules of this are enabled by cargo features of same names.
+
+#[cfg(feature = "nalgebra")]
+pub mod nalgebra;
+#[cfg(feature = "ndarray")]
+pub mod ndarray;
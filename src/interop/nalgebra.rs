@@ -0,0 +1,120 @@
+//! Interoperability with [`nalgebra`].
+//!
+//! [`VArr`] and [`DVector`] are converted to each other without copying.
+//! Laplacian of network is exported as dense or sparse matrix.
+
+use crate::ode::values::{VArr, Value};
+use crate::parts::{NdeqNet, Weight};
+use ::nalgebra::{DMatrix, DVector, DVectorView, DVectorViewMut, RealField, Scalar};
+use ::nalgebra_sparse::{CooMatrix, CsrMatrix};
+
+impl<T> VArr<T>
+where
+    T: Scalar,
+{
+    /// Returns vector view of this value array.
+    pub fn as_dvector_view(&self) -> DVectorView<'_, T> {
+        DVectorView::from_slice(self.as_ref(), self.len())
+    }
+
+    /// Returns mutable vector view of this value array.
+    pub fn as_dvector_view_mut(&mut self) -> DVectorViewMut<'_, T> {
+        let len = self.len();
+        DVectorViewMut::from_slice(self.as_mut(), len)
+    }
+}
+
+impl<T> From<VArr<T>> for DVector<T>
+where
+    T: Scalar,
+{
+    fn from(value: VArr<T>) -> Self {
+        let vec = value.into_vec();
+        DVector::from_vec(vec)
+    }
+}
+
+impl<T> From<DVector<T>> for VArr<T>
+where
+    T: Scalar,
+{
+    fn from(value: DVector<T>) -> Self {
+        VArr::new(value.data.into())
+    }
+}
+
+/// Returns Laplacian matrix of network at time `t` as dense matrix.
+///
+/// See [`NdeqNet::laplacian_entries`] for matrix.
+///
+/// # Panics
+///
+/// Panics if `net` or its nodes are currently mutably borrowed.
+pub fn laplacian<V, W>(net: &dyn NdeqNet<V, W>, t: f64) -> DMatrix<W>
+where
+    V: Value,
+    W: Weight + RealField,
+{
+    let len = net.nodes_count();
+    let mut ret = DMatrix::zeros(len, len);
+    for (row, col, value) in net.laplacian_entries(t) {
+        ret[(row, col)] += value;
+    }
+
+    ret
+}
+
+/// Returns Laplacian matrix of network at time `t` as sparse matrix.
+///
+/// See [`NdeqNet::laplacian_entries`] for matrix.
+///
+/// # Panics
+///
+/// Panics if `net` or its nodes are currently mutably borrowed.
+pub fn sparse_laplacian<V, W>(net: &dyn NdeqNet<V, W>, t: f64) -> CsrMatrix<W>
+where
+    V: Value,
+    W: Weight + RealField,
+{
+    let len = net.nodes_count();
+    let mut coo = CooMatrix::new(len, len);
+    for (row, col, value) in net.laplacian_entries(t) {
+        coo.push(row, col, value);
+    }
+
+    CsrMatrix::from(&coo)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::TestNet;
+
+    #[test]
+    fn varr_round_trips_dvector() {
+        let mut value = VArr::new(vec![1.0, 2.0, 3.0]);
+        value.as_dvector_view_mut()[1] = 5.0;
+        assert_eq!(value.as_dvector_view().sum(), 9.0);
+
+        let vector = DVector::from(value.clone());
+        assert_eq!(vector, DVector::from_vec(vec![1.0, 5.0, 3.0]));
+        assert_eq!(VArr::from(vector), value);
+    }
+
+    #[test]
+    fn laplacian_gives_slope() {
+        for net in TestNet::variants() {
+            let value = VArr::new(net.values());
+            let mut expected = value.clone();
+            net.slope()(&mut expected, 0.0, &value);
+
+            let x = DVector::from(value);
+            let dense = -laplacian(&net, 0.0) * &x;
+            let sparse = -(&sparse_laplacian(&net, 0.0) * &x);
+            for idx in 0..4 {
+                assert!((dense[idx] - expected[idx]).abs() < 1e-12);
+                assert!((sparse[idx] - expected[idx]).abs() < 1e-12);
+            }
+        }
+    }
+}
@@ -0,0 +1,104 @@
+//! Interoperability with [`ndarray`].
+//!
+//! [`VArr`] and [`Array1`] are converted to each other without copying (if
+//! array is in standard layout). Laplacian of network is exported as dense
+//! matrix.
+
+use crate::ode::values::{VArr, Value};
+use crate::parts::{NdeqNet, Weight};
+use ::ndarray::{Array1, Array2, ArrayView1, ArrayViewMut1};
+
+impl<T> VArr<T> {
+    /// Returns array view of this value array.
+    pub fn as_array_view(&self) -> ArrayView1<'_, T> {
+        ArrayView1::from(self.as_ref().as_slice())
+    }
+
+    /// Returns mutable array view of this value array.
+    pub fn as_array_view_mut(&mut self) -> ArrayViewMut1<'_, T> {
+        ArrayViewMut1::from(self.as_mut().as_mut_slice())
+    }
+}
+
+impl<T> From<VArr<T>> for Array1<T> {
+    fn from(value: VArr<T>) -> Self {
+        let vec = value.into_vec();
+        Array1::from_vec(vec)
+    }
+}
+
+impl<T> From<Array1<T>> for VArr<T>
+where
+    T: Clone,
+{
+    fn from(value: Array1<T>) -> Self {
+        if !value.is_standard_layout() {
+            return VArr::new(value.to_vec());
+        }
+
+        let len = value.len();
+        let (mut vec, offset) = value.into_raw_vec_and_offset();
+        let offset = offset.unwrap_or(0);
+        if offset != 0 || vec.len() != len {
+            vec = vec[offset..offset + len].to_vec();
+        }
+
+        VArr::new(vec)
+    }
+}
+
+/// Returns Laplacian matrix of network at time `t` as dense matrix.
+///
+/// See [`NdeqNet::laplacian_entries`] for matrix.
+///
+/// # Panics
+///
+/// Panics if `net` or its nodes are currently mutably borrowed.
+pub fn laplacian<V, W>(net: &dyn NdeqNet<V, W>, t: f64) -> Array2<W>
+where
+    V: Value,
+    W: Weight,
+{
+    let len = net.nodes_count();
+    let mut ret = Array2::from_elem((len, len), W::default());
+    for (row, col, value) in net.laplacian_entries(t) {
+        ret[(row, col)] += value;
+    }
+
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::TestNet;
+    use ::ndarray::{array, s};
+
+    #[test]
+    fn varr_round_trips_array() {
+        let mut value = VArr::new(vec![1.0, 2.0, 3.0]);
+        value.as_array_view_mut()[1] = 5.0;
+        assert_eq!(value.as_array_view().sum(), 9.0);
+
+        let array = Array1::from(value.clone());
+        assert_eq!(array, array![1.0, 5.0, 3.0]);
+        assert_eq!(VArr::from(array), value);
+
+        let strided = array![1.0, 2.0, 3.0, 4.0].slice_move(s![1..;2]);
+        assert_eq!(VArr::from(strided), VArr::new(vec![2.0, 4.0]));
+    }
+
+    #[test]
+    fn laplacian_gives_slope() {
+        for net in TestNet::variants() {
+            let value = VArr::new(net.values());
+            let mut expected = value.clone();
+            net.slope()(&mut expected, 0.0, &value);
+
+            let actual = -laplacian(&net, 0.0).dot(&Array1::from(value));
+            for idx in 0..4 {
+                assert!((actual[idx] - expected[idx]).abs() < 1e-12);
+            }
+        }
+    }
+}
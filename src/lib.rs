@@ -5,9 +5,12 @@
 
 #![warn(missing_docs)]
 
+pub mod fit;
+pub mod interop;
 pub mod net_ode;
 pub mod ode;
 pub mod parts;
 pub mod prelude;
+pub mod uncertainty;
 
 mod util;
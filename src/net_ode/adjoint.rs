@@ -0,0 +1,354 @@
+//! Provider of [`Adjoint`].
+
+use crate::net_ode::solver::NetOdeSolver;
+use crate::net_ode::{Gradient, NdeqSim};
+use crate::parts::{EdgeMode, Laplacian, NdeqNet, Weight};
+
+/// Adjoint sensitivity solver of linear network diffusion.
+///
+/// This calculates gradient of scalar loss `J(x(t))` by all edge weights
+/// and initial node values with one backward pass. So, its cost does not
+/// depend on parameters count (unlike forward sensitivity of
+/// [`NdeqSim::set_sensitivity`]).
+///
+/// Forward pass (see [`forward`](Self::forward)) runs simulator, and stores
+/// node values at checkpoints. Backward pass (see [`backward`](Self::backward))
+/// recomputes node values between checkpoints, and integrates adjoint system
+/// `dl/dt = L^T * l` backward by the fourth order Runge-Kutta method
+/// (`L` is Laplacian matrix of [`NdeqNet::laplacian_entries`]). Derivatives
+/// by edge weights are integrated by trapezoidal rule.
+///
+/// Backward pass uses the fourth order Runge-Kutta method regardless of ODE
+/// solver of forward pass. So, result is gradient of exact solution (not of
+/// discrete forward pass), and it is consistent with forward pass only up to
+/// error of its solver (like `O(h)` for
+/// [`NetEuler`](crate::net_ode::solver::adapters::NetEuler)). Use
+/// [`NetRungeKutta`](crate::net_ode::solver::adapters::NetRungeKutta) for
+/// consistent gradient.
+///
+/// Target network must have linear diffusion with [`Laplacian::Combinatorial`]
+/// and no advection edges. Fixed nodes, capacities, constant sources and
+/// time-varying weights are supported. Derivative by weight of time-varying
+/// edge is derivative by constant shift of the weight.
+pub struct Adjoint<'a, W = f32> {
+    /// Target network.
+    net: &'a dyn NdeqNet<f64, W>,
+
+    /// Simulator of forward pass.
+    sim: NdeqSim<'a, f64, f64, W>,
+
+    /// Step size.
+    h: f64,
+
+    /// Steps count between checkpoints.
+    interval: usize,
+
+    /// Checkpoints of forward pass (start time, steps count and node values).
+    checkpoints: Vec<(f64, usize, Vec<f64>)>,
+
+    /// Time and node values at the end of forward pass.
+    last: Option<(f64, Vec<f64>)>,
+}
+
+impl<'a, W> Adjoint<'a, W>
+where
+    W: Weight,
+{
+    /// Creates a new instance.
+    ///
+    /// Step size is taken from `solver` (see [`NetOdeSolver::step_size`]).
+    /// Checkpoint is stored every `interval` steps. So, memory usage is
+    /// proportional to `steps / interval + interval` node values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the following occurs.
+    ///
+    /// * Step size of `solver` is not fixed, or it is zero or negative or NaN
+    ///   or infinity.
+    /// * `interval` is zero.
+    pub fn new(
+        net: &'a dyn NdeqNet<f64, W>,
+        solver: &'a dyn NetOdeSolver<f64, f64>,
+        interval: usize,
+    ) -> Self {
+        let h = solver.step_size().expect(msg::NO_STEP);
+        assert!(h.is_finite() && h > 0.0, "{}", msg::INVALID_STEP);
+        assert!(interval > 0, "{}", msg::INVALID_INTERVAL);
+        Self {
+            net,
+            sim: NdeqSim::new(net, solver),
+            h,
+            interval,
+            checkpoints: Vec::new(),
+            last: None,
+        }
+    }
+
+    /// Returns simulator of forward pass.
+    pub fn sim(&self) -> &NdeqSim<'a, f64, f64, W> {
+        &self.sim
+    }
+
+    /// Update target network node values to future values, and stores checkpoints.
+    ///
+    /// Duration `t` is rounded to multiple of step size. Previous checkpoints
+    /// are discarded.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the following occurs.
+    ///
+    /// * `t` is negative or NaN or infinity.
+    /// * Target network is not supported (see type document).
+    pub fn forward(&mut self, t: f64) {
+        assert!(t.is_finite() && t >= 0.0, "{}", msg::INVALID_DURATION);
+        assert!(
+            self.net.laplacian() == Laplacian::Combinatorial,
+            "{}",
+            msg::LAPLACIAN
+        );
+        assert!(self.net.advections().next().is_none(), "{}", msg::ADVECTION);
+
+        let mut rest = (t / self.h).round() as usize;
+        self.checkpoints.clear();
+        while rest > 0 {
+            let steps = rest.min(self.interval);
+            let mut values = Vec::new();
+            self.net.export_values(&mut values);
+            self.checkpoints.push((self.sim.time(), steps, values));
+            self.sim.run(steps as f64 * self.h);
+            rest -= steps;
+        }
+
+        let mut values = Vec::new();
+        self.net.export_values(&mut values);
+        self.last = Some((self.sim.time(), values));
+    }
+
+    /// Returns gradient of loss by edge weights and initial node values.
+    ///
+    /// `dj` is gradient of loss by node values at the end of forward pass.
+    /// During this, node values of target network are overwritten by
+    /// recomputed values. They are restored at the end.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the following occurs.
+    ///
+    /// * Forward pass has not been run.
+    /// * `dj` length is not equal to nodes count.
+    /// * Target network or its nodes are currently borrowed.
+    pub fn backward(&mut self, dj: &[f64]) -> Gradient {
+        let (last_time, last_values) = self.last.clone().expect(msg::NO_FORWARD);
+        assert_eq!(dj.len(), last_values.len(), "{}", msg::LEN_MISSMATCH);
+
+        let system = System::new(self.net, dj.len());
+        let mut lambda = dj.to_vec();
+        let mut weights = vec![0.0; system.edges.len()];
+        let mut states = Vec::new();
+        let checkpoints = std::mem::take(&mut self.checkpoints);
+        for (time, steps, values) in checkpoints.iter().rev() {
+            self.recompute(&mut states, *time, *steps, values);
+            for n in (0..*steps).rev() {
+                let t1 = time + (n + 1) as f64 * self.h;
+                system.add_weights(&mut weights, &states[n + 1], &lambda, self.h / 2.0);
+                system.step_back(&mut lambda, t1, self.h);
+                system.add_weights(&mut weights, &states[n], &lambda, self.h / 2.0);
+            }
+        }
+
+        self.checkpoints = checkpoints;
+        self.net.import_values(&last_values);
+        self.sim.set_time(last_time);
+        Gradient {
+            weights,
+            values: lambda,
+        }
+    }
+
+    /// Recomputes node values of each step from checkpoint.
+    fn recompute(&mut self, states: &mut Vec<Vec<f64>>, time: f64, steps: usize, values: &[f64]) {
+        states.clear();
+        states.push(values.to_vec());
+        self.net.import_values(values);
+        self.sim.set_time(time);
+        for _ in 0..steps {
+            self.sim.run(self.h);
+            let mut values = Vec::new();
+            self.net.export_values(&mut values);
+            states.push(values);
+        }
+    }
+}
+
+/// Adjoint system of network.
+struct System<'a, W> {
+    /// Target network.
+    net: &'a dyn NdeqNet<f64, W>,
+
+    /// Edge mode of network.
+    mode: EdgeMode,
+
+    /// Edges (backward and forward node indices).
+    edges: Vec<(usize, usize)>,
+
+    /// Masks of nodes (zero for fixed nodes).
+    masks: Vec<f64>,
+
+    /// Reciprocal capacities of nodes.
+    scales: Vec<f64>,
+
+    /// Laplacian entries (`None` if network is time-varying).
+    entries: Option<Vec<(usize, usize, W)>>,
+}
+
+impl<'a, W> System<'a, W>
+where
+    W: Weight,
+{
+    /// Creates a new instance.
+    fn new(net: &'a dyn NdeqNet<f64, W>, len: usize) -> Self {
+        let mut masks = vec![1.0; len];
+        let mut scales = vec![1.0; len];
+        for idx in net.fixed_nodes() {
+            masks[idx] = 0.0;
+        }
+
+        for (idx, c) in net.capacities() {
+            scales[idx] = 1.0 / c;
+        }
+
+        Self {
+            net,
+            mode: net.edge_mode(),
+            edges: net.edges().map(|(b, f, _)| (b, f)).collect(),
+            masks,
+            scales,
+            entries: (!net.is_time_varying()).then(|| net.laplacian_entries(0.0)),
+        }
+    }
+
+    /// Adds integrand of weight derivatives at node values `x` multiplied by `h`.
+    fn add_weights(&self, result: &mut [f64], x: &[f64], lambda: &[f64], h: f64) {
+        let adjoint = |idx: usize| lambda[idx] * self.masks[idx] * self.scales[idx];
+        for (k, &(bwd_idx, fwd_idx)) in self.edges.iter().enumerate() {
+            let mut sum = 0.0;
+            if self.mode != EdgeMode::OutFlow {
+                sum += adjoint(bwd_idx);
+            }
+
+            if self.mode != EdgeMode::InFlow {
+                sum -= adjoint(fwd_idx);
+            }
+
+            result[k] += h * sum * (x[fwd_idx] - x[bwd_idx]);
+        }
+    }
+
+    /// Advances adjoint values from time `t` to `t - h`.
+    fn step_back(&self, lambda: &mut [f64], t: f64, h: f64) {
+        let k1 = self.slope(t, lambda);
+        let k2 = self.slope(t - h / 2.0, &axpy(lambda, -h / 2.0, &k1));
+        let k3 = self.slope(t - h / 2.0, &axpy(lambda, -h / 2.0, &k2));
+        let k4 = self.slope(t - h, &axpy(lambda, -h, &k3));
+        for idx in 0..lambda.len() {
+            let sum = k1[idx] + 2.0 * k2[idx] + 2.0 * k3[idx] + k4[idx];
+            lambda[idx] -= h / 6.0 * sum;
+        }
+    }
+
+    /// Returns slope of adjoint values (`L^T * M * l`) at time `t`.
+    fn slope(&self, t: f64, lambda: &[f64]) -> Vec<f64> {
+        let owned;
+        let entries = match &self.entries {
+            Some(entries) => entries,
+            None => {
+                owned = self.net.laplacian_entries(t);
+                &owned
+            }
+        };
+
+        let mut ret = vec![0.0; lambda.len()];
+        for &(row, col, value) in entries {
+            ret[col] += value.to_f64() * self.masks[row] * lambda[row];
+        }
+
+        ret
+    }
+}
+
+/// Returns `x + a * y`.
+fn axpy(x: &[f64], a: f64, y: &[f64]) -> Vec<f64> {
+    x.iter().zip(y).map(|(x, y)| x + a * y).collect()
+}
+
+mod msg {
+    pub const NO_STEP: &str = "ODE solver must have fixed step size.";
+    pub const INVALID_STEP: &str = "Step size must be positive and finite.";
+    pub const INVALID_INTERVAL: &str = "Checkpoint interval must be positive.";
+    pub const INVALID_DURATION: &str = "Duration must be non-negative and finite.";
+    pub const LAPLACIAN: &str = "Adjoint supports only combinatorial Laplacian.";
+    pub const ADVECTION: &str = "Adjoint does not support advection edges.";
+    pub const NO_FORWARD: &str = "Forward pass has not been run.";
+    pub const LEN_MISSMATCH: &str = "Loss gradient and node values count missmatch.";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net_ode::solver::adapters::NetRungeKutta;
+    use crate::ode::Slope;
+    use crate::ode::solver::OdeSolver;
+    use crate::ode::values::VArr;
+    use crate::util::{PATH_VALUES, PATH_WEIGHTS, TestNet, finite_diff};
+    use std::rc::Rc;
+
+    /// Network ODE solver without fixed step size (like adaptive solvers).
+    struct Adaptive(NetRungeKutta<f64, f64>);
+
+    impl NetOdeSolver<f64, f64> for Adaptive {
+        fn create<'a>(
+            &self,
+            slope: Rc<Slope<'a, f64, VArr<f64>>>,
+        ) -> Box<dyn OdeSolver<'a, f64, VArr<f64>> + 'a> {
+            self.0.create(slope)
+        }
+    }
+
+    /// Returns loss `sum(x^2) / 2` at the end of diffusion of path fixture.
+    fn loss(values: [f64; 3], w: [f64; 2]) -> Vec<f64> {
+        let net = TestNet::path(values.to_vec(), w);
+        let solver = NetRungeKutta::new(0.01);
+        let mut sim = NdeqSim::new(&net, &solver);
+        sim.run(1.0);
+        vec![net.values().iter().map(|x| x * x / 2.0).sum()]
+    }
+
+    #[test]
+    fn backward_matches_finite_differences() {
+        let net = TestNet::path(PATH_VALUES.to_vec(), PATH_WEIGHTS);
+        let solver = NetRungeKutta::new(0.01);
+        let mut adjoint = Adjoint::new(&net, &solver, 10);
+        adjoint.forward(1.0);
+        let gradient = adjoint.backward(&net.values());
+
+        for k in 0..2 {
+            let fd = finite_diff(|w| loss(PATH_VALUES, w), PATH_WEIGHTS, k);
+            assert!((gradient.weights[k] - fd[0]).abs() < 1e-5);
+        }
+
+        for idx in 0..3 {
+            let fd = finite_diff(|x| loss(x, PATH_WEIGHTS), PATH_VALUES, idx);
+            assert!((gradient.values[idx] - fd[0]).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "ODE solver must have fixed step size.")]
+    fn new_rejects_solver_without_step_size() {
+        let net = TestNet::new(vec![1.0, 0.0], vec![(0, 1, 1.0)]);
+        let solver = Adaptive(NetRungeKutta::new(0.01));
+        Adjoint::new(&net, &solver, 10);
+    }
+}
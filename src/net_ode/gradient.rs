@@ -0,0 +1,15 @@
+//! Provider of [`Gradient`].
+
+/// Gradient of scalar loss by network parameters.
+///
+/// This is result of [`Adjoint::backward`](crate::net_ode::Adjoint::backward).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Gradient {
+    /// Derivatives by edge weights (same order as [`NdeqNet::edges`]).
+    ///
+    /// [`NdeqNet::edges`]: crate::parts::NdeqNet::edges
+    pub weights: Vec<f64>,
+
+    /// Derivatives by initial node values (same order as node indices).
+    pub values: Vec<f64>,
+}
@@ -0,0 +1,113 @@
+//! Provider of [`Invariant`].
+
+use crate::ode::values::{RF64, Value};
+use std::rc::Rc;
+
+/// Invariant of network node values.
+///
+/// This is quantity which should be conserved by simulation (like total
+/// mass). Simulator measures it at each run, and reports its drift. If
+/// invariant has projection, simulator can also correct drift with it.
+pub struct Invariant<'a, V> {
+    /// Measure closure.
+    measure: Rc<Measure<'a, V>>,
+
+    /// Projection closure.
+    project: Option<Rc<Project<'a, V>>>,
+}
+
+/// Measure closure of invariant.
+type Measure<'a, V> = dyn Fn(&mut V, &[V]) + 'a;
+
+/// Projection closure of invariant.
+type Project<'a, V> = dyn Fn(&mut [V], &V, &V) + 'a;
+
+impl<'a, V> Invariant<'a, V>
+where
+    V: Value,
+{
+    /// Creates a new instance.
+    ///
+    /// Closure measures invariant of node values of the second argument,
+    /// and writes it to the first argument.
+    pub fn new<F>(f: F) -> Self
+    where
+        F: Fn(&mut V, &[V]) + 'a,
+    {
+        Self {
+            measure: Rc::new(f),
+            project: None,
+        }
+    }
+
+    /// Creates a new instance of total mass of nodes.
+    ///
+    /// Each item of `nodes` is `(idx, capacity)`, and total mass is
+    /// `sum_i(c_i * x_i)`. Projection shifts all of the nodes uniformly.
+    pub fn mass(nodes: Vec<(usize, f64)>) -> Self {
+        let nodes = Rc::new(nodes);
+        let total = nodes.iter().map(|&(_, c)| c).sum::<f64>();
+        let measure = {
+            let nodes = nodes.clone();
+            move |result: &mut V, values: &[V]| {
+                let mut work = V::default();
+                result.fill_zero();
+                for &(idx, c) in nodes.iter() {
+                    work.clone_from(&values[idx]);
+                    work *= RF64(c);
+                    *result += &work;
+                }
+            }
+        };
+
+        Self::new(measure).with_projection(move |values, target, current| {
+            let mut shift = target.clone();
+            shift -= current;
+            shift /= RF64(total);
+            for &(idx, _) in nodes.iter() {
+                values[idx] += &shift;
+            }
+        })
+    }
+
+    /// Returns this instance with projection.
+    ///
+    /// Closure corrects node values of the first argument so that invariant
+    /// becomes target value of the second argument. The third argument is
+    /// current invariant value.
+    pub fn with_projection<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&mut [V], &V, &V) + 'a,
+    {
+        self.project = Some(Rc::new(f));
+        self
+    }
+
+    /// Writes invariant of `values` to `result`.
+    pub fn measure(&self, result: &mut V, values: &[V]) {
+        (self.measure)(result, values)
+    }
+
+    /// Returns `true` if `self` has projection.
+    pub fn has_projection(&self) -> bool {
+        self.project.is_some()
+    }
+
+    /// Corrects `values` so that invariant becomes `target`.
+    ///
+    /// If `self` has no projection, this does nothing.
+    pub fn project(&self, values: &mut [V], target: &V, current: &V) {
+        if let Some(project) = &self.project {
+            project(values, target, current)
+        }
+    }
+}
+
+impl<V> Clone for Invariant<'_, V> {
+    fn clone(&self) -> Self {
+        Self {
+            measure: self.measure.clone(),
+            project: self.project.clone(),
+        }
+    }
+}
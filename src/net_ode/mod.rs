@@ -2,6 +2,14 @@
 
 pub mod solver;
 
+pub use adjoint::*;
+pub use gradient::*;
+pub use invariant::*;
 pub use ndeq_sim::*;
+pub use source::*;
 
+mod adjoint;
+mod gradient;
+mod invariant;
 mod ndeq_sim;
+mod source;
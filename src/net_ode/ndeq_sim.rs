@@ -1,47 +1,1328 @@
 //! Provider of [`NdeqSim`].
 
 use crate::net_ode::solver::NetOdeSolver;
+use crate::net_ode::{Invariant, Source};
+use crate::ode::ode_util;
 use crate::ode::solver::OdeSolver;
-use crate::ode::values::{Time, VArr, Value};
+use crate::ode::values::{RF64, Tangents, Time, VArr, Value};
+use crate::parts::flux_limiters::Upwind;
 use crate::prelude::*;
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, HashMap};
 use std::ops::MulAssign;
+use std::rc::Rc;
 
 /// Network diffusion simulator.
-pub struct NdeqSim<'a, T, V> {
+///
+/// Type parameter `W` is edge weight type of network (see [`Weight`]).
+///
+/// # Topology changes
+///
+/// Network topology can be changed between [`run`](Self::run) calls. Then,
+/// simulator detects it and rebuilds cached structures of network and ODE
+/// solver workspaces.
+///
+/// If network has stable node ids (see [`NdeqNet::export_ids`]), node values
+/// of nodes kept by the change are remapped from the end of last run by their
+/// ids, and node values of added nodes are exported from network. So, node
+/// values follow their nodes even if network stores them by position. Settings
+/// of simulator (like prescribed values, sources and sensitivity edges) also
+/// follow node ids. Without node ids, node values are exported from network
+/// as they are.
+///
+/// # Boundary nodes
+///
+/// Fixed nodes (see [`NdeqNet::fixed_nodes`]) and prescribed nodes (see
+/// [`prescribe`](Self::prescribe)) are boundary nodes. They are excluded
+/// from integration, but their neighbors still exchange flow with them.
+///
+/// # Flow law
+///
+/// Network diffusion uses linear flow law by default. Other flow law can be
+/// set by [`set_flow_law`](Self::set_flow_law).
+///
+/// # Advection
+///
+/// Advection edges of network (see [`NdeqNet::advections`]) are added to
+/// diffusion slope by upwind flux. Flux limiter can be set by
+/// [`set_flux_limiter`](Self::set_flux_limiter).
+///
+/// # Sources
+///
+/// Sources of network (see [`NdeqNet::sources`]) and sources of simulator
+/// (see [`add_source`](Self::add_source)) are added to diffusion slope.
+///
+/// # Reaction
+///
+/// Reaction of simulator (see [`set_reaction`](Self::set_reaction)) is
+/// added to diffusion slope of all nodes. So, simulator can solve
+/// reaction-diffusion system `dx_i/dt = sum_j(w_ij * (x_j - x_i)) + f(x_i, t)`.
+/// (Reaction of specific node can be added as source of the node.)
+///
+/// # Positivity
+///
+/// Simulator passes production-destruction form of slope (see
+/// [`NdeqNet::pd_terms`]) to ODE solver factory too if factory uses it (see
+/// [`NetOdeSolver::is_pd`]). So, Patankar-type solvers like
+/// [`NetPatankar`](crate::net_ode::solver::adapters::NetPatankar) keep node
+/// values non-negative with any step size.
+///
+/// # Invariants
+///
+/// Simulator can monitor total mass of each connected component (see
+/// [`set_conservation`](Self::set_conservation)) and custom invariants (see
+/// [`add_invariant`](Self::add_invariant)). Reference value of invariant is
+/// measured at the first run after it is registered, and drift from it is
+/// measured at the end of each run. If drift correction is enabled (see
+/// [`set_drift_correction`](Self::set_drift_correction)), node values are
+/// projected back to reference values.
+///
+/// Total mass is conserved only by undirected diffusion with combinatorial
+/// Laplacian without boundary nodes, sources and reaction. Boundary nodes are
+/// excluded from it, and components are rebuilt when boundary nodes are
+/// changed.
+///
+/// # Sensitivity
+///
+/// If node values have tangents (like [`Dual`](crate::ode::values::Dual)),
+/// simulator can calculate derivatives of node values by edge weights (see
+/// [`set_sensitivity`](Self::set_sensitivity)) without finite differences.
+///
+/// Derivatives by initial values need no sensitivity edges. Tangents of node
+/// values are propagated by solver, so seed tangent `k` of initial value of
+/// node by one, and tangent `k` of node values is their derivative by it.
+///
+/// ```
+/// use ndeq::net_ode::NdeqSim;
+/// use ndeq::net_ode::solver::adapters::NetEuler;
+/// use ndeq::ode::values::Dual;
+/// use ndeq::parts::NdeqNet;
+/// use std::cell::RefCell;
+///
+/// struct Pair(RefCell<Vec<Dual<f64, 1>>>);
+///
+/// impl NdeqNet<Dual<f64, 1>, f64> for Pair {
+///     fn edges(&self) -> Box<dyn Iterator<Item = (usize, usize, f64)> + '_> {
+///         Box::new([(0, 1, 1.0)].into_iter())
+///     }
+///
+///     fn import_values(&self, values: &[Dual<f64, 1>]) {
+///         self.0.borrow_mut().clone_from_slice(values);
+///     }
+///
+///     fn export_values(&self, values: &mut Vec<Dual<f64, 1>>) {
+///         values.clone_from(&self.0.borrow());
+///     }
+/// }
+///
+/// // Derivatives by initial value of node 0.
+/// let values = vec![Dual::<f64, 1>::variable(1.0, 0), Dual::<f64, 1>::constant(0.0)];
+/// let net = Pair(RefCell::new(values));
+/// let solver = NetEuler::new(0.01);
+/// let mut sim = NdeqSim::new(&net, &solver);
+/// sim.run(1.0);
+///
+/// // Node 0 is pulled to node 1 (`x0' = x1 - x0`) by 100 steps.
+/// let dx_dx0 = sim.sensitivities(0).collect::<Vec<_>>();
+/// assert!((dx_dx0[0] - 0.99_f64.powi(100)).abs() < 1e-12);
+/// assert_eq!(dx_dx0[1], 0.0);
+/// ```
+pub struct NdeqSim<'a, T, V, W = f32> {
     /// Network.
-    net: &'a dyn NdeqNet<V>,
+    net: &'a dyn NdeqNet<V, W>,
+
+    /// ODE solver factory.
+    factory: &'a dyn NetOdeSolver<T, V>,
 
     /// ODE solver.
     solver: Box<dyn OdeSolver<'a, T, VArr<V>> + 'a>,
 
+    /// Flow law (`None` means linear flow law).
+    flow_law: Option<Rc<dyn FlowLaw<V, W> + 'a>>,
+
+    /// Flux limiter of advection.
+    flux_limiter: Rc<dyn FluxLimiter<V> + 'a>,
+
+    /// Simulation time.
+    time: T,
+
     /// Network node values.
     values: VArr<V>,
+
+    /// Network node values at the end of last run.
+    last: VArr<V>,
+
+    /// Network topology at last run.
+    topology: Topology,
+
+    /// Prescribed value functions by stable node ids.
+    prescribed: HashMap<usize, Prescribed<'a, T, V>>,
+
+    /// Sources with stable node ids.
+    sources: Vec<(usize, Source<'a, T, V>)>,
+
+    /// Reaction of all nodes.
+    reaction: Option<Source<'a, T, V>>,
+
+    /// Slope terms at current run.
+    terms: Rc<Terms<'a, T, V>>,
+
+    /// `true` if total mass of components is monitored.
+    conservation: bool,
+
+    /// `true` if drift of invariants is corrected.
+    drift_correction: bool,
+
+    /// Total mass monitors of components (built at run).
+    masses: Vec<Monitor<'a, V>>,
+
+    /// Custom invariant monitors.
+    invariants: Vec<Monitor<'a, V>>,
+
+    /// Edges of weight sensitivity by stable node ids.
+    sensitivity: Vec<(usize, usize)>,
 }
 
-impl<'a, T, V> NdeqSim<'a, T, V>
+impl<'a, T, V, W> NdeqSim<'a, T, V, W>
 where
     T: Time,
     V: Value + MulAssign<T>,
+    W: Weight,
 {
     /// Creates a new instance.
-    pub fn new(net: &'a dyn NdeqNet<V>, solver: &dyn NetOdeSolver<T, V>) -> Self {
-        Self {
+    ///
+    /// `solver` is borrowed for lifetime of simulator (not only for this
+    /// call), because ODE solver is recreated by it when topology or flow law
+    /// is changed.
+    pub fn new(net: &'a dyn NdeqNet<V, W>, solver: &'a dyn NetOdeSolver<T, V>) -> Self {
+        let mut values = VArr::default();
+        net.export_values(values.as_mut());
+        let mut ret = Self {
             net,
-            solver: solver.create(net),
-            values: Default::default(),
-        }
+            factory: solver,
+            solver: solver.create(ode_util::flat_slope()),
+            flow_law: None,
+            flux_limiter: Rc::new(Upwind),
+            time: T::zero(),
+            topology: Topology::new(net, values.len()),
+            values,
+            last: VArr::default(),
+            prescribed: HashMap::new(),
+            sources: Vec::new(),
+            reaction: None,
+            terms: Default::default(),
+            conservation: false,
+            drift_correction: false,
+            masses: Vec::new(),
+            invariants: Vec::new(),
+            sensitivity: Vec::new(),
+        };
+
+        ret.solver = ret.create_solver();
+        ret
     }
 
     /// Returns target network.
-    pub fn net<'s: 'a>(&'s self) -> &'a dyn NdeqNet<V> {
+    pub fn net<'s: 'a>(&'s self) -> &'a dyn NdeqNet<V, W> {
         self.net
     }
 
+    /// Returns simulation time.
+    ///
+    /// This is absolute time, and it is advanced by [`run`](Self::run).
+    /// Slope of each solver step receives this time (not elapsed time in run).
+    pub fn time(&self) -> T {
+        self.time
+    }
+
+    /// Sets simulation time.
+    pub fn set_time(&mut self, value: T) {
+        self.time = value;
+    }
+
+    /// Returns node index of stable node id at last run.
+    ///
+    /// See [`NdeqNet::export_ids`] for node ids.
+    pub fn idx(&self, id: usize) -> Option<usize> {
+        self.topology.idx(id)
+    }
+
+    /// Sets flow law of network edges.
+    pub fn set_flow_law(&mut self, law: Rc<dyn FlowLaw<V, W> + 'a>) {
+        self.flow_law = Some(law);
+        self.solver = self.create_solver();
+    }
+
+    /// Resets flow law of network edges to linear flow law.
+    pub fn clear_flow_law(&mut self) {
+        self.flow_law = None;
+        self.solver = self.create_solver();
+    }
+
+    /// Sets flux limiter of advection.
+    pub fn set_flux_limiter(&mut self, limiter: Rc<dyn FluxLimiter<V> + 'a>) {
+        self.flux_limiter = limiter;
+    }
+
+    /// Prescribes node value by function of simulation time.
+    ///
+    /// Node is specified by stable node id (see [`NdeqNet::export_ids`]).
+    /// `f` is evaluated at each solver stage time, so neighbors of the node
+    /// see prescribed value at the stage time. Node value is also set to it
+    /// at the start and end of each run.
+    pub fn prescribe<F>(&mut self, id: usize, f: F)
+    where
+        F: Fn(T) -> V + 'a,
+    {
+        self.prescribed.insert(id, Rc::new(f));
+        self.masses.clear();
+    }
+
+    /// Removes node value prescription.
+    pub fn unprescribe(&mut self, id: usize) {
+        self.prescribed.remove(&id);
+        self.masses.clear();
+    }
+
+    /// Adds source of node.
+    ///
+    /// Node is specified by stable node id (see [`NdeqNet::export_ids`]).
+    /// Multiple sources of same node are summed.
+    pub fn add_source(&mut self, id: usize, source: Source<'a, T, V>) {
+        self.sources.push((id, source));
+    }
+
+    /// Removes all sources of node.
+    pub fn remove_sources(&mut self, id: usize) {
+        self.sources.retain(|(x, _)| *x != id);
+    }
+
+    /// Sets reaction of all nodes.
+    ///
+    /// Reaction is given as [`Source`], and it is applied to each node.
+    pub fn set_reaction(&mut self, reaction: Source<'a, T, V>) {
+        self.reaction = Some(reaction);
+    }
+
+    /// Removes reaction of all nodes.
+    pub fn clear_reaction(&mut self) {
+        self.reaction = None;
+    }
+
+    /// Enables or disables monitoring of total mass of each connected component.
+    ///
+    /// Mass of node is its value multiplied by its capacity (see
+    /// [`NdeqNet::capacities`]). Components are rebuilt when topology is
+    /// changed, and then their reference values are measured again.
+    pub fn set_conservation(&mut self, enabled: bool) {
+        self.conservation = enabled;
+        self.masses.clear();
+    }
+
+    /// Enables or disables drift correction of invariants.
+    ///
+    /// Only invariants with projection are corrected. Total mass is corrected
+    /// only if it is really conserved (see type document). If mass monitoring
+    /// is enabled (see [`set_conservation`](Self::set_conservation)) with
+    /// boundary nodes, sources, reaction, directed edges or normalized
+    /// Laplacian, [`run`](Self::run) panics instead of cancelling their real
+    /// mass changes.
+    pub fn set_drift_correction(&mut self, enabled: bool) {
+        self.drift_correction = enabled;
+    }
+
+    /// Adds custom invariant.
+    pub fn add_invariant(&mut self, invariant: Invariant<'a, V>) {
+        self.invariants.push(Monitor::new(invariant));
+    }
+
+    /// Removes all custom invariants.
+    pub fn clear_invariants(&mut self) {
+        self.invariants.clear();
+    }
+
+    /// Resets reference values of invariants.
+    ///
+    /// Reference values are measured again at the next run. This is useful
+    /// after node values are changed intentionally.
+    pub fn reset_invariants(&mut self) {
+        let monitors = self.masses.iter_mut().chain(&mut self.invariants);
+        monitors.for_each(|x| x.reference = None);
+    }
+
+    /// Returns drifts of total mass of components at last run.
+    ///
+    /// Components are ordered by their smallest node index. Drift is
+    /// measured before correction.
+    pub fn mass_drifts(&self) -> impl Iterator<Item = &V> + '_ {
+        self.masses.iter().map(|x| &x.drift)
+    }
+
+    /// Returns drifts of custom invariants at last run.
+    ///
+    /// Invariants are ordered as they are added. Drift is measured before
+    /// correction.
+    pub fn drifts(&self) -> impl Iterator<Item = &V> + '_ {
+        self.invariants.iter().map(|x| &x.drift)
+    }
+
+    /// Sets edges of weight sensitivity.
+    ///
+    /// Edge is specified by stable node ids of its backward and forward nodes
+    /// (same order as [`NdeqNet::edges`]), and it must be an edge of network
+    /// at each run (see [`run`](Self::run)). If network wraps other network
+    /// (like [`CompiledNet`]), edge is resolved against target network (see
+    /// [`NdeqNet::target`]). After this, tangent `k` of node
+    /// values is advanced with derivative of slope by weight of `edges[k]`.
+    /// So, if initial tangent is zero, tangent `k` of node values is their
+    /// derivative by the weight (see [`sensitivities`](Self::sensitivities)).
+    ///
+    /// Derivative of flow by weight is assumed to be flow with unit weight
+    /// (this is true for built-in flow laws). Tangents are propagated through
+    /// any explicit solver. Laplacian of network must be
+    /// [`Laplacian::Combinatorial`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `edges` count is greater than tangent components count.
+    pub fn set_sensitivity(&mut self, edges: Vec<(usize, usize)>)
+    where
+        V: Tangents,
+    {
+        let len = V::default().tangents_len();
+        assert!(edges.len() <= len, "{}", msg::SENSITIVITY_OVERFLOW);
+        self.sensitivity = edges;
+        self.terms.seed.set(Some(V::add_tangent));
+    }
+
+    /// Disables weight sensitivity.
+    pub fn clear_sensitivity(&mut self) {
+        self.sensitivity.clear();
+        self.terms.seed.set(None);
+    }
+
+    /// Returns tangent `k` of node values at last run.
+    ///
+    /// Items are ordered by node index. If weight sensitivity is set (see
+    /// [`set_sensitivity`](Self::set_sensitivity)), they are derivatives of
+    /// node values by weight of edge `k`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is out of tangent components count.
+    pub fn sensitivities(&self, k: usize) -> impl Iterator<Item = V::Scalar> + '_
+    where
+        V: Tangents,
+    {
+        self.values.as_ref().iter().map(move |x| x.tangent(k))
+    }
+
     /// Update target network node values to future values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the following occurs.
+    ///
+    /// * Capacities of network are invalid (see [`NdeqNet::capacities`]).
+    /// * Drift correction of total mass is enabled for non-conservative
+    ///   system (see [`set_drift_correction`](Self::set_drift_correction)).
+    /// * Sensitivity edge is not an edge of network (see
+    ///   [`set_sensitivity`](Self::set_sensitivity)).
     pub fn run(&mut self, t: T) {
+        std::mem::swap(&mut self.last, &mut self.values);
         self.net.export_values(self.values.as_mut());
+        self.sync_topology();
+        self.sync_terms();
+        self.sync_masses();
+        self.apply_prescribed();
+        self.init_invariants();
+        self.solver.set_time(self.time);
         self.solver.set_value(&self.values);
         self.solver.run(t);
-        self.net.import_values(self.solver.new_value().as_ref());
+        self.time = self.solver.time();
+        self.update_values();
+        self.check_invariants();
+        self.apply_prescribed();
+        self.net.import_values(self.values.as_ref());
+    }
+
+    /// Creates ODE solver with slope of network and terms.
+    fn create_solver(&self) -> Box<dyn OdeSolver<'a, T, VArr<V>> + 'a> {
+        let net = self.net;
+        let terms = self.terms.clone();
+        let slope = match self.flow_law.clone() {
+            None => net.slope(),
+            Some(law) => net.flow_slope(law),
+        };
+
+        let law = self.flow_law.clone();
+        let mode = original(net).edge_mode();
+        let work = RefCell::new(<[V; 2]>::default());
+        let staged = RefCell::new(VArr::default());
+        let slope = Rc::new(move |result: &mut VArr<V>, t: T, value: &VArr<V>| {
+            let mut work = work.borrow_mut();
+            let mut staged = staged.borrow_mut();
+            let value = terms.apply_prescribed(&mut staged, t, value);
+            slope(result, t.as_f64(), value);
+            terms.add_sensitivities(result, value, mode, law.as_deref(), &mut work);
+            terms.add_advections(result, value, &mut work);
+            terms.add_sources(result, t, value, &mut work[0]);
+            terms.exclude_boundary(result);
+        });
+
+        if !self.factory.is_pd() {
+            return self.factory.create(slope);
+        }
+
+        let law = self.flow_law.clone();
+        let terms = self.terms.clone();
+        let staged = RefCell::new(VArr::default());
+        let pd_slope = Rc::new(move |result: &mut Vec<_>, t: T, value: &VArr<V>| {
+            let mut staged = staged.borrow_mut();
+            let value = terms.apply_prescribed(&mut staged, t, value);
+            net.pd_terms(result, t.as_f64(), value, law.as_deref());
+            terms.add_pd_advections(result, value);
+            terms.add_pd_sources(result, t, value);
+            terms.exclude_pd_boundary(result);
+        });
+
+        self.factory.create_pd(slope, pd_slope)
+    }
+
+    /// Rebuilds network caches and ODE solver if topology is changed.
+    fn sync_topology(&mut self) {
+        let topology = Topology::new(self.net, self.values.len());
+        if topology == self.topology {
+            return;
+        }
+
+        self.net.refresh();
+        self.solver = self.create_solver();
+        self.remap_values(&topology);
+        self.topology = topology;
+        self.masses.clear();
+    }
+
+    /// Remaps node values of kept nodes from last run by stable node ids.
+    fn remap_values(&mut self, topology: &Topology) {
+        for (id, &idx) in &topology.idxs {
+            if let Some(&last_idx) = self.topology.idxs.get(id) {
+                self.values[idx].clone_from(&self.last[last_idx]);
+            }
+        }
+    }
+
+    /// Collects slope terms with node indices.
+    fn sync_terms(&mut self) {
+        let mut prescribed = self.terms.prescribed.borrow_mut();
+        prescribed.clear();
+        for (id, f) in &self.prescribed {
+            if let Some(idx) = self.topology.idx(*id) {
+                prescribed.push((idx, f.clone()));
+            }
+        }
+
+        let mut boundary = self.terms.boundary.borrow_mut();
+        let mut new_boundary = self.net.fixed_nodes().collect::<Vec<_>>();
+        new_boundary.extend(prescribed.iter().map(|&(idx, _)| idx));
+        new_boundary.sort_unstable();
+        new_boundary.dedup();
+        if *boundary != new_boundary {
+            *boundary = new_boundary;
+            self.masses.clear();
+        }
+
+        let mut sources = self.terms.sources.borrow_mut();
+        sources.clear();
+        for (idx, rate) in self.net.sources() {
+            sources.push((idx, Source::constant(rate)));
+        }
+
+        for (id, source) in &self.sources {
+            if let Some(idx) = self.topology.idx(*id) {
+                sources.push((idx, source.clone()));
+            }
+        }
+
+        let mut reaction = self.terms.reaction.borrow_mut();
+        reaction.clone_from(&self.reaction);
+
+        let mut sensitivity = self.terms.sensitivity.borrow_mut();
+        sensitivity.clear();
+        for (k, &(bwd_id, fwd_id)) in self.sensitivity.iter().enumerate() {
+            let bwd_idx = self.topology.idx(bwd_id).expect(msg::SENSITIVITY_EDGE);
+            let fwd_idx = self.topology.idx(fwd_id).expect(msg::SENSITIVITY_EDGE);
+            let mut edges = original(self.net).edges().map(|(b, f, _)| (b, f));
+            let idx = edges.position(|x| x == (bwd_idx, fwd_idx));
+            let idx = idx.expect(msg::SENSITIVITY_EDGE);
+            sensitivity.push((k, idx, bwd_idx, fwd_idx));
+        }
+
+        let laplacian = self.net.laplacian();
+        let combinatorial = laplacian == Laplacian::Combinatorial;
+        assert!(
+            sensitivity.is_empty() || combinatorial,
+            "{}",
+            msg::SENSITIVITY_LAPLACIAN
+        );
+
+        let mut advections = self.terms.advections.borrow_mut();
+        let mut capacities = self.terms.capacities.borrow_mut();
+        let mut inflows = self.terms.inflows.borrow_mut();
+        advections.clear();
+        advections.extend(self.net.advections());
+        inflows.clear();
+        inflows.resize(self.values.len(), 0.0);
+        for &(from_idx, to_idx, v) in advections.iter() {
+            let receiver_idx = if v >= 0.0 { to_idx } else { from_idx };
+            inflows[receiver_idx] += v.abs();
+        }
+        capacities.clear();
+        capacities.resize(self.values.len(), 1.0);
+        let mut specified = vec![false; self.values.len()];
+        for (idx, c) in self.net.capacities() {
+            assert!(c > 0.0 && c.is_finite(), "{}", msg::INVALID_CAPACITY);
+            assert!(!specified[idx], "{}", msg::DUPLICATE_CAPACITY);
+            specified[idx] = true;
+            capacities[idx] = c;
+        }
+
+        let mut limiter = self.terms.limiter.borrow_mut();
+        *limiter = Some(self.flux_limiter.clone());
+    }
+
+    /// Builds total mass monitors of components if they are needed.
+    fn sync_masses(&mut self) {
+        if !self.conservation {
+            return;
+        }
+
+        if self.drift_correction {
+            let terms = &self.terms;
+            let conservative = terms.boundary.borrow().is_empty()
+                && terms.sources.borrow().is_empty()
+                && terms.reaction.borrow().is_none()
+                && self.net.edge_mode() == EdgeMode::Undirected
+                && self.net.laplacian() == Laplacian::Combinatorial;
+            assert!(conservative, "{}", msg::NON_CONSERVATIVE);
+        }
+
+        if !self.masses.is_empty() {
+            return;
+        }
+
+        let len = self.values.len();
+        let boundary = self.terms.boundary.borrow();
+        let capacities = self.terms.capacities.borrow();
+
+        let mut groups = BTreeMap::<_, Vec<_>>::new();
+        let mut roots = (0..len).collect::<Vec<_>>();
+        for (bwd_idx, fwd_idx, _) in self.net.edges() {
+            let bwd_root = find_root(&mut roots, bwd_idx);
+            let fwd_root = find_root(&mut roots, fwd_idx);
+            roots[bwd_root.max(fwd_root)] = bwd_root.min(fwd_root);
+        }
+
+        for (idx, &c) in capacities.iter().enumerate() {
+            if boundary.binary_search(&idx).is_err() {
+                let root = find_root(&mut roots, idx);
+                groups.entry(root).or_default().push((idx, c));
+            }
+        }
+
+        let invariants = groups.into_values().map(Invariant::mass);
+        self.masses.extend(invariants.map(Monitor::new));
+    }
+
+    /// Measures reference values of new invariants.
+    fn init_invariants(&mut self) {
+        let values = self.values.as_ref();
+        for monitor in self.masses.iter_mut().chain(&mut self.invariants) {
+            monitor.init(values);
+        }
+    }
+
+    /// Measures drift of invariants, and corrects it if needed.
+    fn check_invariants(&mut self) {
+        let values = self.values.as_mut();
+        for monitor in self.masses.iter_mut().chain(&mut self.invariants) {
+            monitor.check(values, self.drift_correction);
+        }
+    }
+
+    /// Sets prescribed node values at current time.
+    fn apply_prescribed(&mut self) {
+        for (&id, f) in &self.prescribed {
+            if let Some(idx) = self.topology.idx(id) {
+                self.values[idx] = f(self.time);
+            }
+        }
+    }
+
+    /// Updates node values except boundary nodes by solver result.
+    fn update_values(&mut self) {
+        let new_value = self.solver.new_value();
+        let boundary = self.terms.boundary.borrow();
+        let mut boundary = boundary.iter().peekable();
+        for idx in 0..self.values.len() {
+            if boundary.next_if_eq(&&idx).is_none() {
+                self.values[idx].clone_from(&new_value[idx]);
+            }
+        }
+    }
+}
+
+/// Slope terms with node indices.
+struct Terms<'a, T, V> {
+    /// Sorted indices of boundary nodes.
+    boundary: RefCell<Vec<usize>>,
+
+    /// Prescribed value functions with node indices.
+    prescribed: RefCell<Vec<(usize, Prescribed<'a, T, V>)>>,
+
+    /// Sources with node indices.
+    sources: RefCell<Vec<(usize, Source<'a, T, V>)>>,
+
+    /// Reaction of all nodes.
+    reaction: RefCell<Option<Source<'a, T, V>>>,
+
+    /// Advection edges.
+    advections: RefCell<Vec<(usize, usize, f64)>>,
+
+    /// Capacities of nodes.
+    capacities: RefCell<Vec<f64>>,
+
+    /// Sums of advection velocities into nodes.
+    inflows: RefCell<Vec<f64>>,
+
+    /// Upstream values of nodes for flux limiter.
+    upstream: RefCell<VArr<V>>,
+
+    /// Flux limiter of advection.
+    limiter: RefCell<Option<Rc<dyn FluxLimiter<V> + 'a>>>,
+
+    /// Sensitivity edges.
+    sensitivity: RefCell<Vec<SensitivityEdge>>,
+
+    /// Tangent seeding function (`None` if sensitivity is disabled).
+    seed: Cell<Option<Seed<V>>>,
+}
+
+/// Prescribed value function of simulation time.
+type Prescribed<'a, T, V> = Rc<dyn Fn(T) -> V + 'a>;
+
+/// Sensitivity edge (tangent index, edge index, backward and forward node indices).
+type SensitivityEdge = (usize, usize, usize, usize);
+
+/// Function which adds primal part of the second value to tangent of the first value.
+type Seed<V> = fn(&mut V, usize, &V);
+
+impl<T, V> Terms<'_, T, V>
+where
+    T: Time,
+    V: Value,
+{
+    /// Adds derivatives of slope by sensitivity edge weights to tangents.
+    fn add_sensitivities<W: Weight>(
+        &self,
+        result: &mut VArr<V>,
+        value: &VArr<V>,
+        mode: EdgeMode,
+        law: Option<&dyn FlowLaw<V, W>>,
+        work: &mut [V; 2],
+    ) {
+        let Some(seed) = self.seed.get() else {
+            return;
+        };
+
+        let capacities = self.capacities.borrow();
+        let [flow, share] = work;
+        for &(k, idx, bwd_idx, fwd_idx) in self.sensitivity.borrow().iter() {
+            let (bwd_value, fwd_value) = (&value[bwd_idx], &value[fwd_idx]);
+            let unit = W::from_f64(1.0);
+            match law {
+                Some(law) => law.edge_flow(flow, idx, unit, bwd_value, fwd_value),
+                None => {
+                    flow.clone_from(fwd_value);
+                    *flow -= bwd_value;
+                }
+            }
+
+            if mode != EdgeMode::OutFlow {
+                share.clone_from(flow);
+                *share /= RF64(capacities[bwd_idx]);
+                seed(&mut result[bwd_idx], k, share);
+            }
+
+            if mode != EdgeMode::InFlow {
+                share.clone_from(flow);
+                *share /= RF64(-capacities[fwd_idx]);
+                seed(&mut result[fwd_idx], k, share);
+            }
+        }
+    }
+
+    /// Updates upstream values of nodes (see [`FluxLimiter`]).
+    fn sync_upstream(&self, value: &VArr<V>, share: &mut V) {
+        let inflows = self.inflows.borrow();
+        let mut upstream = self.upstream.borrow_mut();
+        upstream.clone_zero(value);
+        for &(from_idx, to_idx, v) in self.advections.borrow().iter() {
+            let (donor_idx, receiver_idx) = match v >= 0.0 {
+                true => (from_idx, to_idx),
+                false => (to_idx, from_idx),
+            };
+
+            share.clone_from(&value[donor_idx]);
+            *share *= RF64(v.abs());
+            upstream[receiver_idx] += share;
+        }
+
+        for (idx, &inflow) in inflows.iter().enumerate() {
+            match inflow != 0.0 {
+                true => upstream[idx] /= RF64(inflow),
+                false => upstream[idx].clone_from(&value[idx]),
+            }
+        }
+    }
+
+    /// Adds advective flux to slope.
+    fn add_advections(&self, result: &mut VArr<V>, value: &VArr<V>, work: &mut [V; 2]) {
+        let limiter = self.limiter.borrow();
+        let Some(limiter) = limiter.as_ref() else {
+            return;
+        };
+
+        if self.advections.borrow().is_empty() {
+            return;
+        }
+
+        self.sync_upstream(value, &mut work[0]);
+        let capacities = self.capacities.borrow();
+        let upstream = self.upstream.borrow();
+        let [flux, share] = work;
+        for &(from_idx, to_idx, v) in self.advections.borrow().iter() {
+            let (donor_idx, receiver_idx) = match v >= 0.0 {
+                true => (from_idx, to_idx),
+                false => (to_idx, from_idx),
+            };
+
+            let (donor_value, receiver_value) = (&value[donor_idx], &value[receiver_idx]);
+            limiter.flux(
+                flux,
+                v.abs(),
+                &upstream[donor_idx],
+                donor_value,
+                receiver_value,
+            );
+            share.clone_from(flux);
+            *share /= RF64(capacities[donor_idx]);
+            result[donor_idx] -= share;
+            share.clone_from(flux);
+            *share /= RF64(capacities[receiver_idx]);
+            result[receiver_idx] += share;
+        }
+    }
+
+    /// Adds sources and reaction to slope.
+    fn add_sources(&self, result: &mut VArr<V>, t: T, value: &VArr<V>, work: &mut V) {
+        for (idx, source) in self.sources.borrow().iter() {
+            source.rate(work, t, &value[*idx]);
+            result[*idx] += work;
+        }
+
+        if let Some(reaction) = self.reaction.borrow().as_ref() {
+            for idx in 0..value.len() {
+                reaction.rate(work, t, &value[idx]);
+                result[idx] += work;
+            }
+        }
+    }
+
+    /// Adds advective flux to production-destruction terms.
+    fn add_pd_advections(&self, result: &mut Vec<(usize, usize, V)>, value: &VArr<V>) {
+        let limiter = self.limiter.borrow();
+        let Some(limiter) = limiter.as_ref() else {
+            return;
+        };
+
+        if self.advections.borrow().is_empty() {
+            return;
+        }
+
+        self.sync_upstream(value, &mut V::default());
+        let capacities = self.capacities.borrow();
+        let upstream = self.upstream.borrow();
+        for &(from_idx, to_idx, v) in self.advections.borrow().iter() {
+            let (donor_idx, receiver_idx) = match v >= 0.0 {
+                true => (from_idx, to_idx),
+                false => (to_idx, from_idx),
+            };
+
+            let mut flux = V::default();
+            let (donor_value, receiver_value) = (&value[donor_idx], &value[receiver_idx]);
+            limiter.flux(
+                &mut flux,
+                v.abs(),
+                &upstream[donor_idx],
+                donor_value,
+                receiver_value,
+            );
+            let mut gain = flux.clone();
+            gain /= RF64(capacities[receiver_idx]);
+            flux /= RF64(-capacities[donor_idx]);
+            result.push((receiver_idx, donor_idx, gain));
+            result.push((donor_idx, donor_idx, flux));
+        }
+    }
+
+    /// Adds sources and reaction to production-destruction terms.
+    fn add_pd_sources(&self, result: &mut Vec<(usize, usize, V)>, t: T, value: &VArr<V>) {
+        for (idx, source) in self.sources.borrow().iter() {
+            let mut rate = V::default();
+            source.rate(&mut rate, t, &value[*idx]);
+            result.push((*idx, *idx, rate));
+        }
+
+        if let Some(reaction) = self.reaction.borrow().as_ref() {
+            for idx in 0..value.len() {
+                let mut rate = V::default();
+                reaction.rate(&mut rate, t, &value[idx]);
+                result.push((idx, idx, rate));
+            }
+        }
+    }
+
+    /// Removes production-destruction terms of boundary nodes.
+    fn exclude_pd_boundary(&self, result: &mut Vec<(usize, usize, V)>) {
+        let boundary = self.boundary.borrow();
+        if !boundary.is_empty() {
+            result.retain(|(idx, _, _)| boundary.binary_search(idx).is_err());
+        }
+    }
+
+    /// Returns node values with prescribed values at time `t`.
+    ///
+    /// If there are no prescribed nodes, `value` is returned as it is.
+    /// Otherwise, it is copied to `staged` and prescribed values are set.
+    fn apply_prescribed<'b>(
+        &self,
+        staged: &'b mut VArr<V>,
+        t: T,
+        value: &'b VArr<V>,
+    ) -> &'b VArr<V> {
+        let prescribed = self.prescribed.borrow();
+        if prescribed.is_empty() {
+            return value;
+        }
+
+        staged.as_mut().clone_from(value.as_ref());
+        for (idx, f) in prescribed.iter() {
+            staged[*idx] = f(t);
+        }
+
+        staged
+    }
+
+    /// Clears slope of boundary nodes.
+    fn exclude_boundary(&self, result: &mut VArr<V>) {
+        for &idx in self.boundary.borrow().iter() {
+            result[idx].fill_zero();
+        }
+    }
+}
+
+impl<T, V> Default for Terms<'_, T, V> {
+    fn default() -> Self {
+        Self {
+            boundary: Default::default(),
+            prescribed: Default::default(),
+            sources: Default::default(),
+            reaction: Default::default(),
+            advections: Default::default(),
+            capacities: Default::default(),
+            inflows: Default::default(),
+            upstream: RefCell::new(VArr::new(Vec::new())),
+            limiter: Default::default(),
+            sensitivity: Default::default(),
+            seed: Cell::new(None),
+        }
+    }
+}
+
+/// Invariant monitor.
+struct Monitor<'a, V> {
+    /// Invariant.
+    invariant: Invariant<'a, V>,
+
+    /// Reference value (`None` if not measured yet).
+    reference: Option<V>,
+
+    /// Drift at last run.
+    drift: V,
+
+    /// Working value.
+    work: V,
+}
+
+impl<'a, V> Monitor<'a, V>
+where
+    V: Value,
+{
+    /// Creates a new instance.
+    fn new(invariant: Invariant<'a, V>) -> Self {
+        Self {
+            invariant,
+            reference: None,
+            drift: V::default(),
+            work: V::default(),
+        }
+    }
+
+    /// Measures reference value if it is not measured yet.
+    fn init(&mut self, values: &[V]) {
+        if self.reference.is_none() {
+            self.invariant.measure(&mut self.work, values);
+            self.reference = Some(self.work.clone());
+            self.drift.fill_zero();
+        }
+    }
+
+    /// Measures drift, and corrects it if `correct` is `true`.
+    fn check(&mut self, values: &mut [V], correct: bool) {
+        let Some(reference) = &self.reference else {
+            return;
+        };
+
+        self.invariant.measure(&mut self.work, values);
+        self.drift.clone_from(&self.work);
+        self.drift -= reference;
+        if correct {
+            self.invariant.project(values, reference, &self.work);
+        }
+    }
+}
+
+/// Returns root of union-find tree (with path halving).
+fn find_root(roots: &mut [usize], mut idx: usize) -> usize {
+    while roots[idx] != idx {
+        roots[idx] = roots[roots[idx]];
+        idx = roots[idx];
+    }
+
+    idx
+}
+
+/// Returns innermost target network of wrapper networks (see [`NdeqNet::target`]).
+fn original<V: Value, W: Weight>(net: &dyn NdeqNet<V, W>) -> &dyn NdeqNet<V, W> {
+    let mut ret = net;
+    while let Some(target) = ret.target() {
+        ret = target;
+    }
+
+    ret
+}
+
+/// Network topology summary.
+#[derive(PartialEq)]
+struct Topology {
+    /// Nodes count.
+    len: usize,
+
+    /// Hash of edges.
+    hash: u64,
+
+    /// Node indices by stable node ids.
+    idxs: HashMap<usize, usize>,
+}
+
+impl Topology {
+    /// Creates a new instance.
+    fn new<V: Value, W: Weight>(net: &dyn NdeqNet<V, W>, len: usize) -> Self {
+        let mut ids = Vec::new();
+        net.export_ids(&mut ids);
+        assert!(ids.is_empty() || ids.len() == len, "{}", msg::IDS_MISSMATCH);
+        Self {
+            len,
+            hash: net.topology_hash(),
+            idxs: ids.into_iter().enumerate().map(|(i, id)| (id, i)).collect(),
+        }
+    }
+
+    /// Returns node index of stable node id.
+    fn idx(&self, id: usize) -> Option<usize> {
+        match self.idxs.is_empty() {
+            true => (id < self.len).then_some(id),
+            false => self.idxs.get(&id).copied(),
+        }
+    }
+}
+
+mod msg {
+    pub const IDS_MISSMATCH: &str = "Node ids and node values count missmatch.";
+    pub const NON_CONSERVATIVE: &str =
+        "Drift correction of total mass requires conservative system.";
+    pub const INVALID_CAPACITY: &str = "Node capacity must be positive and finite.";
+    pub const DUPLICATE_CAPACITY: &str = "Node capacity is specified more than once.";
+    pub const SENSITIVITY_OVERFLOW: &str =
+        "Sensitivity edges count is greater than tangent components count.";
+    pub const SENSITIVITY_LAPLACIAN: &str =
+        "Weight sensitivity is supported only for combinatorial Laplacian.";
+    pub const SENSITIVITY_EDGE: &str = "Sensitivity edge is not an edge of network.";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net_ode::solver::adapters::{NetEuler, NetRungeKutta};
+    use crate::ode::values::Dual;
+    use crate::parts::flux_limiters::VanLeer;
+    use crate::util::{PATH_VALUES, PATH_WEIGHTS, TestNet, finite_diff};
+
+    /// Returns node values of pulse on ring advected by flux limiter.
+    fn advect_pulse(limiter: Rc<dyn FluxLimiter<f64>>) -> Vec<f64> {
+        let values = (0..20)
+            .map(|i| f64::from(u8::from((3..8).contains(&i))))
+            .collect();
+        let mut net = TestNet::<f64>::new(values, Vec::new());
+        net.advections = (0..20).map(|i| (i, (i + 1) % 20, 1.0)).collect();
+        let solver = NetEuler::new(0.25);
+        let mut sim = NdeqSim::new(&net, &solver);
+        sim.set_flux_limiter(limiter);
+        sim.run(10.0);
+        net.values()
+    }
+
+    #[test]
+    fn flux_limiter_keeps_pulse_sharp_and_positive() {
+        let upwind = advect_pulse(Rc::new(Upwind));
+        let van_leer = advect_pulse(Rc::new(VanLeer));
+        let max = |values: &[f64]| values.iter().copied().fold(f64::MIN, f64::max);
+
+        assert!(van_leer.iter().all(|&x| x >= -1e-12));
+        assert!((van_leer.iter().sum::<f64>() - 5.0).abs() < 1e-9);
+        assert!((upwind.iter().sum::<f64>() - 5.0).abs() < 1e-9);
+        assert!(max(&van_leer) > max(&upwind) + 0.1);
+    }
+
+    #[test]
+    fn mass_monitor_follows_boundary_changes() {
+        let net = TestNet::<f64>::new(vec![1.0, 0.0, 0.0], vec![(0, 1, 1.0), (1, 2, 1.0)]);
+        *net.fixed.borrow_mut() = vec![0];
+        let solver = NetEuler::new(0.1);
+        let mut sim = NdeqSim::new(&net, &solver);
+        sim.set_conservation(true);
+        sim.run(1.0);
+        sim.run(1.0);
+        assert!(*sim.mass_drifts().next().unwrap() > 0.0);
+
+        net.fixed.borrow_mut().clear();
+        sim.set_drift_correction(true);
+        sim.run(1.0);
+        assert_eq!(sim.mass_drifts().count(), 1);
+        assert!(sim.mass_drifts().next().unwrap().abs() < 1e-12);
+    }
+
+    #[test]
+    fn prescribed_ramp_drives_neighbor() {
+        // `dx_1/dt = t - x_1` with `x_1(0) = 0` is solved by `t - 1 + e^-t`.
+        let net = TestNet::<f64>::new(vec![0.0, 0.0], vec![(0, 1, 1.0)]);
+        let solver = NetRungeKutta::new(0.01);
+        let mut sim = NdeqSim::new(&net, &solver);
+        sim.prescribe(0, |t| t);
+        sim.run(1.0);
+        sim.run(1.0);
+
+        let values = net.values();
+        let expected = 1.0 + (-2.0f64).exp();
+        assert!((values[0] - 2.0).abs() < 1e-12);
+        assert!((values[1] - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn prescribed_values_keep_fixed_nodes() {
+        let net = TestNet::<f64>::new(vec![0.0, 0.0, 3.0], vec![(0, 1, 1.0), (1, 2, 1.0)]);
+        *net.fixed.borrow_mut() = vec![2];
+        let solver = NetRungeKutta::new(0.01);
+        let mut sim = NdeqSim::new(&net, &solver);
+        sim.prescribe(0, |t| 2.0 * t);
+        sim.run(1.0);
+
+        let values = net.values();
+        assert_eq!(values[0], 2.0);
+        assert_eq!(values[2], 3.0);
+        assert!(values[1] > 0.0 && values[1] < 3.0);
+    }
+
+    #[test]
+    fn sources_are_added_to_slope() {
+        // Diffusion keeps total mass, so it grows by integrals of sources.
+        let net = TestNet::<f64>::new(vec![0.0, 0.0], vec![(0, 1, 1.0)]);
+        let solver = NetRungeKutta::new(0.01);
+        let mut sim = NdeqSim::new(&net, &solver);
+        sim.add_source(0, Source::constant(1.0));
+        sim.add_source(1, Source::time(|t| t));
+        sim.add_source(1, Source::constant(-0.5));
+        sim.run(2.0);
+
+        assert!((net.values().iter().sum::<f64>() - 3.0).abs() < 1e-12);
+
+        sim.remove_sources(0);
+        sim.remove_sources(1);
+        sim.run(1.0);
+        assert!((net.values().iter().sum::<f64>() - 3.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn reaction_is_integrated_at_all_nodes() {
+        // Uniform values do not diffuse, and logistic growth `x (1 - x)`
+        // from `1/2` is solved by `1 / (1 + e^-t)`.
+        let net = TestNet::<f64>::new(vec![0.5, 0.5, 0.5], vec![(0, 1, 1.0), (1, 2, 1.0)]);
+        let solver = NetRungeKutta::new(0.01);
+        let mut sim = NdeqSim::new(&net, &solver);
+        sim.set_reaction(Source::new(|r, _, x| *r = x * (1.0 - x)));
+        sim.run(2.0);
+
+        let expected = 1.0 / (1.0 + (-2.0f64).exp());
+        for x in net.values() {
+            assert!((x - expected).abs() < 1e-9);
+        }
+
+        sim.clear_reaction();
+        sim.run(1.0);
+        for x in net.values() {
+            assert!((x - expected).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn f64_precision_beats_f32() {
+        // Values of path of two nodes converge by `(1 + e^(-2t)) / 2`.
+        let expected = (1.0 + (-2.0f64).exp()) / 2.0;
+
+        let net = TestNet::<f64, f64>::new(vec![1.0, 0.0], vec![(0, 1, 1.0)]);
+        let solver = NetRungeKutta::new(0.001);
+        NdeqSim::new(&net, &solver).run(1.0);
+        let error64 = (net.values()[0] - expected).abs();
+
+        let net = TestNet::<f32, f32>::new(vec![1.0, 0.0], vec![(0, 1, 1.0)]);
+        let solver = NetRungeKutta::new(0.001);
+        NdeqSim::new(&net, &solver).run(1.0);
+        let error32 = (f64::from(net.values()[0]) - expected).abs();
+
+        assert!(error64 < 1e-12);
+        assert!(error64 * 1e3 < error32);
+    }
+
+    #[test]
+    fn topology_change_remaps_values_by_ids() {
+        let net = TestNet::<f64>::new(vec![1.0, 2.0, 3.0], vec![]);
+        *net.ids.borrow_mut() = vec![10, 11, 12];
+        let solver = NetEuler::new(0.1);
+        let mut sim = NdeqSim::new(&net, &solver);
+        sim.run(1.0);
+
+        // Node 11 is removed, and node 13 is added. Network stores values by
+        // position, so values of kept nodes are stale.
+        *net.ids.borrow_mut() = vec![13, 12, 10];
+        *net.values.borrow_mut() = vec![5.0, -1.0, -1.0];
+        sim.run(1.0);
+
+        assert_eq!(net.values(), [5.0, 3.0, 1.0]);
+        assert_eq!(sim.idx(10), Some(2));
+    }
+
+    #[test]
+    #[should_panic(expected = "Drift correction of total mass requires conservative system.")]
+    fn drift_correction_rejects_sources() {
+        let net = TestNet::<f64>::new(vec![1.0, 0.0], vec![(0, 1, 1.0)]);
+        let solver = NetEuler::new(0.1);
+        let mut sim = NdeqSim::new(&net, &solver);
+        sim.set_conservation(true);
+        sim.set_drift_correction(true);
+        sim.add_source(0, Source::constant(1.0));
+        sim.run(1.0);
+    }
+
+    /// Returns node values of network after diffusion.
+    fn diffuse<V>(net: &dyn NdeqNet<V, f64>, sensitivity: bool) -> Vec<V>
+    where
+        V: Value + MulAssign<f64> + Tangents,
+    {
+        let solver = NetEuler::new(0.01);
+        let mut sim = NdeqSim::new(net, &solver);
+        if sensitivity {
+            sim.set_sensitivity(vec![(0, 1), (1, 2)]);
+        }
+
+        sim.run(1.0);
+        let mut ret = Vec::new();
+        net.export_values(&mut ret);
+        ret
+    }
+
+    #[test]
+    fn sensitivities_match_finite_differences() {
+        let duals = || PATH_VALUES.map(Dual::<f64, 2>::constant).to_vec();
+        let primals = |w| {
+            let values = diffuse(&TestNet::path(duals(), w), false);
+            values.iter().map(|x| x.re).collect()
+        };
+
+        // Compiled network duplicates undirected edges into in-flow edges.
+        let net = TestNet::path(duals(), PATH_WEIGHTS);
+        let target = TestNet::path(duals(), PATH_WEIGHTS);
+        let compiled = CompiledNet::new(&target);
+        for duals in [diffuse(&net, true), diffuse(&compiled, true)] {
+            for k in 0..2 {
+                let fds = finite_diff(primals, PATH_WEIGHTS, k);
+                for (x, fd) in duals.iter().zip(fds) {
+                    assert!((x.tangent(k) - fd).abs() < 1e-6);
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Sensitivity edge is not an edge of network.")]
+    fn sensitivity_rejects_missing_edge() {
+        let values = vec![Dual::<f64, 1>::constant(1.0); 3];
+        let net = TestNet::new(values, vec![(0, 1, 1.0), (1, 2, 1.0)]);
+        let solver = NetEuler::new(0.1);
+        let mut sim = NdeqSim::new(&net, &solver);
+        sim.set_sensitivity(vec![(0, 2)]);
+        sim.run(1.0);
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn derived_value_diffuses_each_field() {
+        use crate::ode::values::Value;
+
+        #[derive(Clone, Debug, Default, PartialEq, Value)]
+        struct Weather {
+            temperature: f64,
+            humidity: f64,
+        }
+
+        let values = vec![
+            Weather {
+                temperature: 30.0,
+                humidity: 0.2,
+            },
+            Weather {
+                temperature: 10.0,
+                humidity: 0.8,
+            },
+        ];
+        let net = TestNet::new(values, vec![(0, 1, 1.0)]);
+        let solver = NetEuler::new(0.01);
+        let mut sim = NdeqSim::new(&net, &solver);
+        sim.run(10.0);
+
+        for x in net.values() {
+            assert!((x.temperature - 20.0).abs() < 1e-6);
+            assert!((x.humidity - 0.5).abs() < 1e-6);
+        }
     }
 }
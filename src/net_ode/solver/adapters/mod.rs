@@ -1,7 +1,15 @@
 //! Network ODE adapters.
 
+pub use net_crank_nicolson::*;
 pub use net_euler::*;
+pub use net_patankar::*;
+pub use net_positive_guard::*;
 pub use net_runge_kutta::*;
+pub use net_ssp_runge_kutta::*;
 
+mod net_crank_nicolson;
 mod net_euler;
+mod net_patankar;
+mod net_positive_guard;
 mod net_runge_kutta;
+mod net_ssp_runge_kutta;
@@ -0,0 +1,95 @@
+//! Provider of [`NetCrankNicolson`].
+
+use crate::net_ode::solver::NetOdeSolver;
+use crate::ode::Slope;
+use crate::ode::solver::solvers::CrankNicolson;
+use crate::ode::solver::{GpOdeSolver, OdeSolver};
+use crate::ode::values::{Norm, Time, VArr, Value};
+use std::marker::PhantomData;
+use std::ops::MulAssign;
+use std::rc::Rc;
+
+/// ODE solver for network with Crank-Nicolson method.
+///
+/// This is norm-preserving solver for quantum walk (see
+/// [`QuantumWalk`](crate::parts::flow_laws::QuantumWalk)). It is A-stable,
+/// so step size is limited only by accuracy (see [`CrankNicolson`]).
+pub struct NetCrankNicolson<T, V> {
+    h: T,
+    tol: f64,
+    pd: PhantomData<V>,
+}
+
+impl<T, V> NetCrankNicolson<T, V> {
+    /// Creates a new instance.
+    ///
+    /// Tolerance is `1e-10` by default.
+    pub fn new(h: T) -> Self {
+        Self {
+            h,
+            tol: 1e-10,
+            pd: Default::default(),
+        }
+    }
+
+    /// Sets relative tolerance of Newton iteration.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` is negative or NaN.
+    pub fn set_tolerance(&mut self, value: f64) {
+        assert!(value >= 0.0, "{}", msg::INVALID_TOLERANCE);
+        self.tol = value;
+    }
+}
+
+impl<T, V> NetOdeSolver<T, V> for NetCrankNicolson<T, V>
+where
+    T: Time,
+    V: Value + MulAssign<T> + Norm,
+{
+    fn create<'a>(
+        &self,
+        slope: Rc<Slope<'a, T, VArr<V>>>,
+    ) -> Box<dyn OdeSolver<'a, T, VArr<V>> + 'a> {
+        let mut ret = CrankNicolson::new(self.h);
+        ret.set_tolerance(self.tol);
+        ret.set_slope(slope);
+        ret
+    }
+
+    fn step_size(&self) -> Option<T> {
+        Some(self.h)
+    }
+}
+
+mod msg {
+    pub const INVALID_TOLERANCE: &str = "Tolerance must be zero or positive.";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net_ode::NdeqSim;
+    use crate::ode::values::Complex;
+    use crate::parts::flow_laws::QuantumWalk;
+    use crate::util::TestNet;
+
+    #[test]
+    fn quantum_walk_keeps_norm_with_large_step() {
+        let values = vec![
+            Complex::new(1.0, 0.0),
+            Default::default(),
+            Default::default(),
+        ];
+        let net = TestNet::new(values, vec![(0, 1, 1.0), (1, 2, 2.0)]);
+        let solver = NetCrankNicolson::new(0.5);
+        let mut sim = NdeqSim::new(&net, &solver);
+        sim.set_flow_law(Rc::new(QuantumWalk));
+        sim.run(10.0);
+
+        let values = VArr::new(net.values());
+        assert!((values.norm() - 1.0).abs() < 1e-9);
+        assert!(values[2].norm() > 0.0);
+    }
+}
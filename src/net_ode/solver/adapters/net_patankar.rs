@@ -0,0 +1,122 @@
+//! Provider of [`NetPatankar`].
+
+use crate::net_ode::solver::NetOdeSolver;
+use crate::ode::solver::OdeSolver;
+use crate::ode::solver::solvers::Patankar;
+use crate::ode::values::{Time, VArr};
+use crate::ode::{PdSlope, Slope, ode_util};
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::ops::MulAssign;
+use std::rc::Rc;
+
+/// ODE solver for network with modified Patankar-Runge-Kutta method.
+///
+/// If solver is created only with slope (not with production-destruction
+/// form), slope of each node is treated as explicit source (if positive) or
+/// destruction of the node (if negative). This still keeps values
+/// non-negative, but total of values is not conserved.
+pub struct NetPatankar<T, V> {
+    h: T,
+    second_order: bool,
+    pd: PhantomData<V>,
+}
+
+impl<T, V> NetPatankar<T, V> {
+    /// Creates a new instance with MPRK22 method.
+    pub fn new(h: T) -> Self {
+        Self {
+            h,
+            second_order: true,
+            pd: Default::default(),
+        }
+    }
+
+    /// Creates a new instance with modified Patankar-Euler method.
+    pub fn euler(h: T) -> Self {
+        Self {
+            h,
+            second_order: false,
+            pd: Default::default(),
+        }
+    }
+}
+
+macro_rules! impl_net_ode_solver {
+    ($ty:ty) => {
+        impl<T> NetOdeSolver<T, $ty> for NetPatankar<T, $ty>
+        where
+            T: Time,
+            $ty: MulAssign<T>,
+        {
+            fn create<'a>(
+                &self,
+                slope: Rc<Slope<'a, T, VArr<$ty>>>,
+            ) -> Box<dyn OdeSolver<'a, T, VArr<$ty>> + 'a> {
+                let work = RefCell::new(VArr::default());
+                let pd_slope = Rc::new(move |terms: &mut Vec<_>, t, value: &VArr<$ty>| {
+                    let mut work = work.borrow_mut();
+                    slope(&mut work, t, value);
+                    terms.extend(work.as_ref().iter().enumerate().map(|(i, &r)| (i, i, r)));
+                });
+
+                self.create_pd(ode_util::flat_slope(), pd_slope)
+            }
+
+            fn is_pd(&self) -> bool {
+                true
+            }
+
+            fn step_size(&self) -> Option<T> {
+                Some(self.h)
+            }
+
+            fn create_pd<'a>(
+                &self,
+                slope: Rc<Slope<'a, T, VArr<$ty>>>,
+                pd_slope: Rc<PdSlope<'a, T, $ty>>,
+            ) -> Box<dyn OdeSolver<'a, T, VArr<$ty>> + 'a> {
+                let _ = slope;
+                let mut ret = match self.second_order {
+                    true => Patankar::new(self.h),
+                    false => Patankar::euler(self.h),
+                };
+
+                ret.set_pd_slope(pd_slope);
+                ret
+            }
+        }
+    };
+}
+
+impl_net_ode_solver!(f32);
+impl_net_ode_solver!(f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net_ode::NdeqSim;
+    use crate::util::TestNet;
+
+    #[test]
+    fn conserves_total_and_positivity_with_large_step() {
+        for solver in [
+            NetPatankar::euler(0.1),
+            NetPatankar::new(0.1),
+            NetPatankar::euler(5.0),
+            NetPatankar::new(5.0),
+        ] {
+            let mut values = vec![0.0; 10];
+            values[0] = 10.0;
+            let edges = (0..9).map(|i| (i, i + 1, 1.0 + i as f64)).collect();
+            let net = TestNet::<f64>::new(values, edges);
+            let mut sim = NdeqSim::new(&net, &solver);
+            sim.run(500.0);
+
+            let values = net.values();
+            assert!(values.iter().all(|&x| x >= 0.0));
+            assert!((values.iter().sum::<f64>() - 10.0).abs() < 1e-9);
+            assert!(values.iter().all(|&x| (x - 1.0).abs() < 0.1));
+        }
+    }
+}
@@ -0,0 +1,57 @@
+//! Provider of [`NetPositiveGuard`].
+
+use crate::net_ode::solver::NetOdeSolver;
+use crate::ode::solver::OdeSolver;
+use crate::ode::solver::solvers::PositiveGuard;
+use crate::ode::values::{Positivity, Time, VArr, Value};
+use crate::ode::{PdSlope, Slope};
+use std::ops::MulAssign;
+use std::rc::Rc;
+
+/// ODE solver for network with step rejection for negative values.
+///
+/// This wraps other network ODE solver (see [`PositiveGuard`]).
+pub struct NetPositiveGuard<'s, T, V> {
+    solver: &'s dyn NetOdeSolver<T, V>,
+    h: T,
+    min_h: T,
+}
+
+impl<'s, T, V> NetPositiveGuard<'s, T, V> {
+    /// Creates a new instance.
+    ///
+    /// Step size of inner solver should be not less than `h`.
+    pub fn new(solver: &'s dyn NetOdeSolver<T, V>, h: T, min_h: T) -> Self {
+        Self { solver, h, min_h }
+    }
+}
+
+impl<T, V> NetOdeSolver<T, V> for NetPositiveGuard<'_, T, V>
+where
+    T: Time,
+    V: Value + MulAssign<T> + Positivity,
+{
+    fn create<'a>(
+        &self,
+        slope: Rc<Slope<'a, T, VArr<V>>>,
+    ) -> Box<dyn OdeSolver<'a, T, VArr<V>> + 'a> {
+        PositiveGuard::new(self.solver.create(slope), self.h, self.min_h)
+    }
+
+    fn step_size(&self) -> Option<T> {
+        self.solver.step_size()
+    }
+
+    fn is_pd(&self) -> bool {
+        self.solver.is_pd()
+    }
+
+    fn create_pd<'a>(
+        &self,
+        slope: Rc<Slope<'a, T, VArr<V>>>,
+        pd_slope: Rc<PdSlope<'a, T, V>>,
+    ) -> Box<dyn OdeSolver<'a, T, VArr<V>> + 'a> {
+        let solver = self.solver.create_pd(slope, pd_slope);
+        PositiveGuard::new(solver, self.h, self.min_h)
+    }
+}
@@ -1,12 +1,13 @@
 //! Provider of [`NetRungeKutta`].
 
 use crate::net_ode::solver::NetOdeSolver;
+use crate::ode::Slope;
 use crate::ode::solver::solvers::RungeKutta;
 use crate::ode::solver::{GpOdeSolver, OdeSolver};
 use crate::ode::values::{Time, VArr, Value};
-use crate::parts::NdeqNet;
 use std::marker::PhantomData;
 use std::ops::MulAssign;
+use std::rc::Rc;
 
 /// ODE solver for network with Runge-Kutta method.
 pub struct NetRungeKutta<T, V> {
@@ -29,9 +30,16 @@ where
     T: Time,
     V: Value + MulAssign<T>,
 {
-    fn create<'a>(&self, net: &'a dyn NdeqNet<V>) -> Box<dyn OdeSolver<'a, T, VArr<V>> + 'a> {
+    fn create<'a>(
+        &self,
+        slope: Rc<Slope<'a, T, VArr<V>>>,
+    ) -> Box<dyn OdeSolver<'a, T, VArr<V>> + 'a> {
         let mut ret = RungeKutta::new(self.h);
-        ret.set_slope(net.slope());
+        ret.set_slope(slope);
         ret
     }
+
+    fn step_size(&self) -> Option<T> {
+        Some(self.h)
+    }
 }
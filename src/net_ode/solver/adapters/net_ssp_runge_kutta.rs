@@ -0,0 +1,45 @@
+//! Provider of [`NetSspRungeKutta`].
+
+use crate::net_ode::solver::NetOdeSolver;
+use crate::ode::Slope;
+use crate::ode::solver::solvers::SspRungeKutta;
+use crate::ode::solver::{GpOdeSolver, OdeSolver};
+use crate::ode::values::{Time, VArr, Value};
+use std::marker::PhantomData;
+use std::ops::MulAssign;
+use std::rc::Rc;
+
+/// ODE solver for network with strong stability preserving Runge-Kutta method.
+pub struct NetSspRungeKutta<T, V> {
+    h: T,
+    pd: PhantomData<V>,
+}
+
+impl<T, V> NetSspRungeKutta<T, V> {
+    /// Creates a new instance.
+    pub fn new(h: T) -> Self {
+        Self {
+            h,
+            pd: Default::default(),
+        }
+    }
+}
+
+impl<T, V> NetOdeSolver<T, V> for NetSspRungeKutta<T, V>
+where
+    T: Time,
+    V: Value + MulAssign<T>,
+{
+    fn create<'a>(
+        &self,
+        slope: Rc<Slope<'a, T, VArr<V>>>,
+    ) -> Box<dyn OdeSolver<'a, T, VArr<V>> + 'a> {
+        let mut ret = SspRungeKutta::new(self.h);
+        ret.set_slope(slope);
+        ret
+    }
+
+    fn step_size(&self) -> Option<T> {
+        Some(self.h)
+    }
+}
@@ -2,10 +2,44 @@
 
 use crate::ode::solver::OdeSolver;
 use crate::ode::values::VArr;
-use crate::parts::NdeqNet;
+use crate::ode::{PdSlope, Slope};
+use std::rc::Rc;
 
 /// ODE solver for network.
 pub trait NetOdeSolver<T, V> {
-    /// Creates ODE solver.
-    fn create<'a>(&self, net: &'a dyn NdeqNet<V>) -> Box<dyn OdeSolver<'a, T, VArr<V>> + 'a>;
+    /// Creates ODE solver with network slope.
+    fn create<'a>(
+        &self,
+        slope: Rc<Slope<'a, T, VArr<V>>>,
+    ) -> Box<dyn OdeSolver<'a, T, VArr<V>> + 'a>;
+
+    /// Returns `true` if this uses production-destruction form of slope.
+    ///
+    /// Simulator builds production-destruction form only if this returns
+    /// `true` (see [`create_pd`](Self::create_pd)). Default implementation
+    /// returns `false`.
+    fn is_pd(&self) -> bool {
+        false
+    }
+
+    /// Returns fixed step size of created ODE solvers.
+    ///
+    /// Default implementation returns `None` (step size is not fixed or not
+    /// known, like adaptive solvers).
+    fn step_size(&self) -> Option<T> {
+        None
+    }
+
+    /// Creates ODE solver with network slope and its production-destruction form.
+    ///
+    /// `pd_slope` represents same system as `slope`, and it is used by
+    /// Patankar-type solvers. Default implementation ignores it.
+    fn create_pd<'a>(
+        &self,
+        slope: Rc<Slope<'a, T, VArr<V>>>,
+        pd_slope: Rc<PdSlope<'a, T, V>>,
+    ) -> Box<dyn OdeSolver<'a, T, VArr<V>> + 'a> {
+        let _ = pd_slope;
+        self.create(slope)
+    }
 }
@@ -0,0 +1,66 @@
+//! Provider of [`Source`].
+
+use crate::ode::Slope;
+use crate::ode::values::{RF64, Value};
+use std::rc::Rc;
+
+/// Source term of network node (forcing term or reaction term).
+///
+/// This is added to slope of node. Negative source works as sink.
+/// And value-dependent source works as reaction (like logistic growth).
+pub struct Source<'a, T, V> {
+    /// Rate closure.
+    rate: Rc<Slope<'a, T, V>>,
+}
+
+impl<'a, T, V> Source<'a, T, V>
+where
+    V: Value,
+{
+    /// Creates a new instance.
+    ///
+    /// Closure calculates rate at time of the second argument and node value
+    /// of the third argument, and writes it to the first argument.
+    pub fn new<F>(f: F) -> Self
+    where
+        F: Fn(&mut V, T, &V) + 'a,
+    {
+        Self { rate: Rc::new(f) }
+    }
+
+    /// Creates a new instance with constant rate.
+    pub fn constant(rate: V) -> Self {
+        Self::new(move |result, _, _| result.clone_from(&rate))
+    }
+
+    /// Creates a new instance with time-dependent rate.
+    pub fn time<F>(f: F) -> Self
+    where
+        F: Fn(T) -> V + 'a,
+    {
+        Self::new(move |result, t, _| *result = f(t))
+    }
+
+    /// Creates a new instance with rate proportional to node value.
+    ///
+    /// Rate is `k * value`. So, negative `k` works as sink like leak.
+    pub fn linear(k: f64) -> Self {
+        Self::new(move |result, _, value| {
+            result.clone_from(value);
+            *result *= RF64(k);
+        })
+    }
+
+    /// Writes rate to `result`.
+    pub fn rate(&self, result: &mut V, t: T, value: &V) {
+        (self.rate)(result, t, value)
+    }
+}
+
+impl<T, V> Clone for Source<'_, T, V> {
+    fn clone(&self) -> Self {
+        Self {
+            rate: self.rate.clone(),
+        }
+    }
+}
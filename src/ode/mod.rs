@@ -6,6 +6,8 @@ pub mod ode_util;
 pub mod solver;
 pub mod values;
 
+pub use pd_slope::*;
 pub use slope::*;
 
+mod pd_slope;
 mod slope;
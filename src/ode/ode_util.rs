@@ -5,15 +5,17 @@ use crate::ode::values::{Time, Value};
 use std::rc::Rc;
 
 /// Create flat slope.
-pub fn flat_slope<V>() -> Rc<Slope<'static, V>>
+pub fn flat_slope<T, V>() -> Rc<Slope<'static, T, V>>
 where
     V: Value,
 {
-    Rc::new(|grad, values| grad.clone_zero(values))
+    Rc::new(|grad, _, values| grad.clone_zero(values))
 }
 
 /// Run `step` with `h` until the total reaches `t`.
-pub fn run_steps<T>(t: T, h: T, step: &mut dyn FnMut(T))
+///
+/// `step` receives elapsed time at the start of step and step size.
+pub fn run_steps<T>(t: T, h: T, step: &mut dyn FnMut(T, T))
 where
     T: Time,
 {
@@ -23,7 +25,7 @@ where
     let mut x = T::zero();
     while x.abs() < t.abs() {
         let h = adjust_h(h, t, x);
-        step(h);
+        step(x, h);
         x = x + h;
     }
 }
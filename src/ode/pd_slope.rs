@@ -0,0 +1,19 @@
+//! Provider of [`PdSlope`].
+
+use crate::ode::values::VArr;
+
+/// Production-destruction function type.
+///
+/// This represents ODE system `dx_i/dt = sum(r)` of components as rate
+/// terms. Internal closure writes terms at time of the second argument and
+/// point of the third argument to the first argument (the first argument is
+/// cleared before call). Each term is `(idx, weight_idx, r)`, and it is
+/// classified by the sign of rate `r`.
+///
+/// * Positive `r` with other `weight_idx` is production from that component.
+/// * Positive `r` with same `weight_idx` is explicit source.
+/// * Negative `r` is destruction of `idx` component.
+///
+/// Patankar-type solvers weight production by `weight_idx` component and
+/// destruction by `idx` component. So, they keep components non-negative.
+pub type PdSlope<'a, T, V> = dyn Fn(&mut Vec<(usize, usize, V)>, T, &VArr<V>) + 'a;
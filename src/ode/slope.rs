@@ -2,6 +2,7 @@
 
 /// Derivative function type.
 ///
-/// Internal closure calculates slope at point of the second
-/// argument, and writes it to the first argument.
-pub type Slope<'a, V> = dyn Fn(&mut V, &V) + 'a;
+/// Internal closure calculates slope at time of the second argument and
+/// point of the third argument, and writes it to the first argument.
+/// (So, this can represent non-autonomous ODE system.)
+pub type Slope<'a, T, V> = dyn Fn(&mut V, T, &V) + 'a;
@@ -11,5 +11,5 @@ where
     V: Value + MulAssign<T>,
 {
     /// Sets slope of this instance.
-    fn set_slope(&mut self, value: Rc<Slope<'a, V>>);
+    fn set_slope(&mut self, value: Rc<Slope<'a, T, V>>);
 }
@@ -16,6 +16,16 @@ where
     /// Sets value of this instance.
     fn set_value(&mut self, value: &V);
 
+    /// Returns time of this instance.
+    fn time(&self) -> T;
+
+    /// Sets time of this instance.
+    ///
+    /// This is absolute time at the start of next [`run`](Self::run). Slope
+    /// receives absolute time of each evaluation point (including
+    /// intermediate stages of algorithm), and `run` advances this time.
+    fn set_time(&mut self, value: T);
+
     /// Update value to future value.
     ///
     /// `t` can be negative if algorithm supports it.
@@ -0,0 +1,462 @@
+//! Provider of [`CrankNicolson`].
+
+use crate::ode::solver::{GpOdeSolver, OdeSolver};
+use crate::ode::values::{Norm, RF32, RF64, Time, Value};
+use crate::ode::{Slope, ode_util};
+use std::ops::MulAssign;
+use std::rc::Rc;
+
+/// ODE solver by [Crank-Nicolson method] (implicit midpoint rule).
+///
+/// Each step solves midpoint value `z = u + h / 2 * f(z)`, and new value is
+/// `2 * z - u`. For linear slope, this is Crank-Nicolson method. It is second
+/// order and A-stable, and it conserves quadratic invariants (like norm of
+/// quantum walk).
+///
+/// Midpoint value is solved by Newton iterations. Linear system of each
+/// iteration `(I - h / 2 * J) * d = r` (`J` is Jacobian of slope and `r` is
+/// residual) is solved by [GMRES] with Jacobian-vector products by finite
+/// differences. So, linear slope (like network diffusion and quantum walk)
+/// is solved in about one iteration with any step size. Iteration stops when
+/// norm of residual becomes less than tolerance relative to norm of midpoint
+/// value. Tolerance must be larger than rounding error of value type (like
+/// `1e-6` for single-precision value).
+///
+/// # Panics
+///
+/// [`OdeSolver::run`] panics if Newton iteration does not reach tolerance
+/// in 20 iterations.
+///
+/// [Crank-Nicolson method]: https://en.wikipedia.org/wiki/Crank%E2%80%93Nicolson_method
+/// [GMRES]: https://en.wikipedia.org/wiki/Generalized_minimal_residual_method
+pub struct CrankNicolson<'a, T, V> {
+    /// Step size.
+    h: T,
+
+    /// Relative tolerance of Newton iteration.
+    tol: f64,
+
+    /// Time at the start of run.
+    time: T,
+
+    /// Old value.
+    old_value: V,
+
+    /// New value.
+    new_value: V,
+
+    /// Slope closure.
+    slope: Rc<Slope<'a, T, V>>,
+
+    /// Work for midpoint value.
+    mid: V,
+
+    /// Work for slope at midpoint value.
+    grad: V,
+
+    /// Work for residual.
+    res: V,
+
+    /// Work for Newton update.
+    delta: V,
+
+    /// Work for Jacobian-vector product.
+    probe: [V; 2],
+
+    /// Work for GMRES.
+    krylov: Krylov<V>,
+}
+
+impl<T, V> CrankNicolson<'_, T, V>
+where
+    T: Time,
+    V: Value + MulAssign<T> + Norm,
+{
+    /// Maximum iterations count of Newton iteration.
+    const MAX_ITERS: usize = 20;
+
+    /// Relative size of finite difference for Jacobian-vector product.
+    const DIFF_STEP: f64 = 1e-4;
+
+    /// Creates a new instance.
+    ///
+    /// Tolerance is `1e-10` by default.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `h` is zero or negative or NaN or infinity.
+    #[must_use]
+    pub fn new(h: T) -> Box<Self> {
+        assert!(!h.is_nan());
+        assert!(!h.is_infinite());
+        assert!(h > T::zero());
+        Box::new(Self {
+            h,
+            tol: 1e-10,
+            time: T::zero(),
+            old_value: Default::default(),
+            new_value: Default::default(),
+            slope: ode_util::flat_slope(),
+            mid: Default::default(),
+            grad: Default::default(),
+            res: Default::default(),
+            delta: Default::default(),
+            probe: Default::default(),
+            krylov: Default::default(),
+        })
+    }
+
+    /// Sets relative tolerance of Newton iteration.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` is negative or NaN.
+    pub fn set_tolerance(&mut self, value: f64) {
+        assert!(value >= 0.0, "{}", msg::INVALID_TOLERANCE);
+        self.tol = value;
+    }
+
+    /// Advance step.
+    fn step(&mut self, x: T, h: T, slope: Rc<Slope<T, V>>) {
+        assert!(!h.is_nan());
+
+        // z = u + h / 2 * f(z)
+        let half_h = h / RF32(2.0);
+        let t = x + half_h;
+        self.mid.clone_from(&self.old_value);
+        for iter in 0.. {
+            assert!(iter < Self::MAX_ITERS, "{}", msg::NOT_CONVERGED);
+
+            // r = u + h / 2 * f(z) - z
+            slope(&mut self.grad, t, &self.mid);
+            self.res.clone_from(&self.grad);
+            self.res *= half_h;
+            self.res += &self.old_value;
+            self.res -= &self.mid;
+            let target = self.tol * self.mid.norm();
+            if self.res.norm() <= target {
+                break;
+            }
+
+            // (I - h / 2 * J) * d = r
+            self.solve(&*slope, t, half_h, target / 2.0);
+            self.mid += &self.delta;
+        }
+
+        // u' = 2 * z - u
+        self.new_value.clone_from(&self.mid);
+        self.new_value *= RF32(2.0);
+        self.new_value -= &self.old_value;
+        self.old_value.clone_from(&self.new_value);
+    }
+
+    /// Solves Newton update `delta` by restarted GMRES.
+    fn solve(&mut self, slope: &Slope<T, V>, t: T, half_h: T, target: f64) {
+        let dim = Krylov::<V>::DIM;
+        self.delta.clone_zero(&self.res);
+        for restart in 0..Krylov::<V>::MAX_RESTARTS {
+            // v_0 = r - A * d
+            let mut basis = std::mem::take(&mut self.krylov.basis);
+            basis.resize_with(dim + 1, Default::default);
+            if restart == 0 {
+                basis[0].clone_from(&self.res);
+            } else {
+                let delta = std::mem::take(&mut self.delta);
+                self.apply(slope, t, half_h, &delta, &mut basis[0]);
+                self.delta = delta;
+                basis[0] *= RF32(-1.0);
+                basis[0] += &self.res;
+            }
+
+            let beta = basis[0].norm();
+            if beta <= target || !beta.is_finite() {
+                self.krylov.basis = basis;
+                return;
+            }
+
+            basis[0] *= RF64(1.0 / beta);
+            self.krylov.begin(beta);
+            let mut len = 0;
+            while len < dim {
+                let (head, tail) = basis.split_at_mut(len + 1);
+                self.apply(slope, t, half_h, &head[len], &mut tail[0]);
+                let residual = self.krylov.orthogonalize(head, &mut tail[0]);
+                len += 1;
+                if residual <= target || self.krylov.breakdown {
+                    break;
+                }
+            }
+
+            self.krylov.update(&basis[..len], &mut self.delta);
+            self.krylov.basis = basis;
+        }
+    }
+
+    /// Writes `v - h / 2 * J * v` to `result` (`J` is Jacobian at midpoint value).
+    fn apply(&mut self, slope: &Slope<T, V>, t: T, half_h: T, v: &V, result: &mut V) {
+        let v_norm = v.norm();
+        if v_norm == 0.0 {
+            result.clone_zero(v);
+            return;
+        }
+
+        // J * v = (f(z + e * v) - f(z)) / e
+        let mid_norm = self.mid.norm();
+        let e = Self::DIFF_STEP * if mid_norm > 0.0 { mid_norm } else { 1.0 } / v_norm;
+        let [point, grad] = &mut self.probe;
+        point.clone_from(v);
+        *point *= RF64(e);
+        *point += &self.mid;
+        slope(grad, t, point);
+        *grad -= &self.grad;
+        *grad *= RF64(-1.0 / e);
+        *grad *= half_h;
+        result.clone_from(v);
+        *result += grad;
+    }
+}
+
+/// Work of restarted GMRES.
+struct Krylov<V> {
+    /// Orthonormal basis of Krylov subspace.
+    basis: Vec<V>,
+
+    /// Columns of Hessenberg matrix (triangulated by Givens rotations).
+    hess: Vec<Vec<f64>>,
+
+    /// Givens rotations `(cos, sin)`.
+    rots: Vec<(f64, f64)>,
+
+    /// Right-hand side of least squares problem.
+    rhs: Vec<f64>,
+
+    /// `true` if last basis vector is zero (exact solution is found).
+    breakdown: bool,
+
+    /// Work for general.
+    work: V,
+}
+
+impl<V> Krylov<V>
+where
+    V: Value + Norm,
+{
+    /// Dimension of Krylov subspace before restart.
+    const DIM: usize = 20;
+
+    /// Maximum restarts count.
+    const MAX_RESTARTS: usize = 10;
+
+    /// Begins new cycle with norm of initial residual.
+    fn begin(&mut self, beta: f64) {
+        self.hess.clear();
+        self.rots.clear();
+        self.rhs.clear();
+        self.rhs.push(beta);
+        self.breakdown = false;
+    }
+
+    /// Orthogonalizes `w` against `basis`, and returns residual norm estimate.
+    fn orthogonalize(&mut self, basis: &[V], w: &mut V) -> f64 {
+        // Modified Gram-Schmidt.
+        let mut col = Vec::with_capacity(basis.len() + 1);
+        for v in basis {
+            let h = w.dot(v);
+            self.work.clone_from(v);
+            self.work *= RF64(-h);
+            *w += &self.work;
+            col.push(h);
+        }
+
+        let norm = w.norm();
+        self.breakdown = norm == 0.0;
+        if !self.breakdown {
+            *w *= RF64(1.0 / norm);
+        }
+
+        col.push(norm);
+
+        // Applies previous rotations and new rotation.
+        for (i, &(c, s)) in self.rots.iter().enumerate() {
+            let (a, b) = (col[i], col[i + 1]);
+            col[i] = c * a + s * b;
+            col[i + 1] = -s * a + c * b;
+        }
+
+        let j = self.rots.len();
+        let r = col[j].hypot(col[j + 1]);
+        let (c, s) = if r == 0.0 {
+            (1.0, 0.0)
+        } else {
+            (col[j] / r, col[j + 1] / r)
+        };
+        col[j] = r;
+        col[j + 1] = 0.0;
+        self.rots.push((c, s));
+        let g = self.rhs[j];
+        self.rhs[j] = c * g;
+        self.rhs.push(-s * g);
+        self.hess.push(col);
+        self.rhs[j + 1].abs()
+    }
+
+    /// Adds solution of least squares problem to `x`.
+    fn update(&mut self, basis: &[V], x: &mut V) {
+        let len = basis.len();
+        let mut y = self.rhs[..len].to_vec();
+        for i in (0..len).rev() {
+            for k in i + 1..len {
+                y[i] -= self.hess[k][i] * y[k];
+            }
+
+            let d = self.hess[i][i];
+            y[i] = if d == 0.0 { 0.0 } else { y[i] / d };
+        }
+
+        for (v, &y) in basis.iter().zip(&y) {
+            self.work.clone_from(v);
+            self.work *= RF64(y);
+            *x += &self.work;
+        }
+    }
+}
+
+impl<V> Default for Krylov<V>
+where
+    V: Default,
+{
+    fn default() -> Self {
+        Self {
+            basis: Vec::new(),
+            hess: Vec::new(),
+            rots: Vec::new(),
+            rhs: Vec::new(),
+            breakdown: false,
+            work: V::default(),
+        }
+    }
+}
+
+impl<'a, T, V> OdeSolver<'a, T, V> for CrankNicolson<'a, T, V>
+where
+    T: Time,
+    V: Value + MulAssign<T> + Norm,
+{
+    fn new_value(&self) -> &V {
+        &self.new_value
+    }
+
+    fn set_value(&mut self, value: &V) {
+        self.old_value.clone_from(value);
+        self.new_value.clone_zero(value);
+        self.mid.clone_zero(value);
+        self.grad.clone_zero(value);
+        self.res.clone_zero(value);
+        self.delta.clone_zero(value);
+        self.probe.iter_mut().for_each(|x| x.clone_zero(value));
+    }
+
+    fn time(&self) -> T {
+        self.time
+    }
+
+    fn set_time(&mut self, value: T) {
+        self.time = value;
+    }
+
+    fn run(&mut self, t: T) {
+        let h = self.h;
+        let t0 = self.time;
+        let mut step = |x, h| self.step(t0 + x, h, self.slope.clone());
+        ode_util::run_steps(t, h, &mut step);
+        self.time = t0 + t;
+    }
+}
+
+impl<'a, T, V> GpOdeSolver<'a, T, V> for CrankNicolson<'a, T, V>
+where
+    T: Time,
+    V: Value + MulAssign<T> + Norm,
+{
+    fn set_slope(&mut self, value: Rc<Slope<'a, T, V>>) {
+        self.slope = value;
+    }
+}
+
+mod msg {
+    pub const INVALID_TOLERANCE: &str = "Tolerance must be zero or positive.";
+    pub const NOT_CONVERGED: &str = "Newton iteration of Crank-Nicolson step did not converge.";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ode::values::VArr;
+
+    #[test]
+    fn run_matches_trapezoidal_rule() {
+        let mut solver = CrankNicolson::new(0.1);
+        solver.set_slope(Rc::new(|grad: &mut f64, _: f64, value: &f64| {
+            *grad = -value
+        }));
+        solver.set_value(&1.0);
+        solver.run(1.0);
+
+        let expected = (0.95_f64 / 1.05).powi(10);
+        assert!((solver.new_value() - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn stiff_step_is_stable() {
+        let mut solver = CrankNicolson::new(0.1);
+        solver.set_slope(Rc::new(|grad: &mut f64, _: f64, value: &f64| {
+            *grad = -100.0 * value
+        }));
+        solver.set_value(&1.0);
+        solver.run(1.0);
+
+        let expected = (-4.0_f64 / 6.0).powi(10);
+        assert!((solver.new_value() - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn large_step_diffusion_conserves_total() {
+        // Diffusion on path of three nodes (largest eigenvalue is 3).
+        let mut solver = CrankNicolson::new(2.0);
+        solver.set_slope(Rc::new(|grad: &mut VArr<f64>, _: f64, x: &VArr<f64>| {
+            *grad = VArr::new(vec![x[1] - x[0], x[0] - 2.0 * x[1] + x[2], x[1] - x[2]]);
+        }));
+        solver.set_value(&VArr::new(vec![3.0, 0.0, 0.0]));
+        solver.run(100.0);
+
+        for &x in solver.new_value().as_ref() {
+            assert!((x - 1.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn nonlinear_slope_is_second_order() {
+        // `dx/dt = -x^2` from `1` is solved by `1 / (1 + t)`.
+        let error = |h| {
+            let mut solver = CrankNicolson::new(h);
+            solver.set_slope(Rc::new(|grad: &mut f64, _: f64, value: &f64| {
+                *grad = -value * value
+            }));
+            solver.set_value(&1.0);
+            solver.run(1.0);
+            (solver.new_value() - 0.5).abs()
+        };
+
+        let order = (error(0.1) / error(0.05)).log2();
+        assert!((order - 2.0).abs() < 0.1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Newton iteration of Crank-Nicolson step did not converge.")]
+    fn nan_slope_panics() {
+        let mut solver = CrankNicolson::new(0.1);
+        solver.set_slope(Rc::new(|grad: &mut f64, _: f64, _: &f64| *grad = f64::NAN));
+        solver.set_value(&1.0);
+        solver.run(1.0);
+    }
+}
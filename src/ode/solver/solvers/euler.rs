@@ -14,6 +14,9 @@ pub struct Euler<'a, T, V> {
     /// Step size.
     h: T,
 
+    /// Time at the start of run.
+    time: T,
+
     /// Old value.
     old_value: V,
 
@@ -21,7 +24,7 @@ pub struct Euler<'a, T, V> {
     new_value: V,
 
     /// Slope closure.
-    slope: Rc<Slope<'a, V>>,
+    slope: Rc<Slope<'a, T, V>>,
 
     /// Work for general.
     work: V,
@@ -47,6 +50,7 @@ where
         assert!(h > T::zero());
         Box::new(Self {
             h,
+            time: T::zero(),
             old_value: Default::default(),
             new_value: Default::default(),
             slope: ode_util::flat_slope(),
@@ -56,11 +60,12 @@ where
     }
 
     /// Advance step.
-    fn step(&mut self, h: T, slope: Rc<Slope<V>>) {
-        slope(&mut self.grad, &self.old_value);
+    fn step(&mut self, x: T, h: T, slope: Rc<Slope<T, V>>) {
+        slope(&mut self.grad, x, &self.old_value);
         let dy = WorkOn(&mut self.work).set(&self.grad).calc(|x| *x *= h);
         self.new_value.clone_from(&self.old_value);
         self.new_value += dy;
+        self.old_value.clone_from(&self.new_value);
     }
 }
 
@@ -80,10 +85,20 @@ where
         self.grad.clone_zero(value);
     }
 
+    fn time(&self) -> T {
+        self.time
+    }
+
+    fn set_time(&mut self, value: T) {
+        self.time = value;
+    }
+
     fn run(&mut self, t: T) {
         let h = self.h;
-        let mut step = |h| self.step(h, self.slope.clone());
+        let t0 = self.time;
+        let mut step = |x, h| self.step(t0 + x, h, self.slope.clone());
         ode_util::run_steps(t, h, &mut step);
+        self.time = t0 + t;
     }
 }
 
@@ -92,7 +107,50 @@ where
     T: Time,
     V: Value + MulAssign<T>,
 {
-    fn set_slope(&mut self, value: Rc<Slope<'a, V>>) {
+    fn set_slope(&mut self, value: Rc<Slope<'a, T, V>>) {
         self.slope = value;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    #[test]
+    fn run_advances_all_steps() {
+        let mut solver = Euler::new(0.1);
+        solver.set_slope(Rc::new(|grad: &mut f64, _: f64, value: &f64| {
+            *grad = -value
+        }));
+        solver.set_value(&1.0);
+        solver.run(1.0);
+
+        let expected = 0.9_f64.powi(10);
+        assert!((solver.new_value() - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn run_passes_stage_times() {
+        let times = Rc::new(RefCell::new(Vec::new()));
+        let mut solver = Euler::new(0.1);
+        let stage_times = times.clone();
+        solver.set_slope(Rc::new(move |grad: &mut f64, t: f64, _: &f64| {
+            stage_times.borrow_mut().push(t);
+            *grad = t;
+        }));
+        solver.set_time(2.0);
+        solver.set_value(&0.0);
+        solver.run(0.2);
+
+        let expected = [2.0, 2.1];
+        let times = times.borrow();
+        assert_eq!(times.len(), expected.len());
+        for (t, expected) in times.iter().zip(expected) {
+            assert!((t - expected).abs() < 1e-12);
+        }
+
+        assert!((solver.time() - 2.2).abs() < 1e-12);
+        assert!((solver.new_value() - 0.41).abs() < 1e-12);
+    }
+}
@@ -1,7 +1,15 @@
 //! ODE solvers.
 
+pub use crank_nicolson::*;
 pub use euler::*;
+pub use patankar::*;
+pub use positive_guard::*;
 pub use runge_kutta::*;
+pub use ssp_runge_kutta::*;
 
+mod crank_nicolson;
 mod euler;
+mod patankar;
+mod positive_guard;
 mod runge_kutta;
+mod ssp_runge_kutta;
@@ -0,0 +1,281 @@
+//! Provider of [`Patankar`].
+
+use crate::ode::solver::OdeSolver;
+use crate::ode::values::{Time, VArr};
+use crate::ode::{PdSlope, ode_util};
+use std::rc::Rc;
+
+/// ODE solver by [modified Patankar-Runge-Kutta methods].
+///
+/// This solves production-destruction system (see [`PdSlope`]) with
+/// modified Patankar-Euler method (first order) or MPRK22 method (second
+/// order). Both methods keep components non-negative with any step size,
+/// and they conserve total of components if each production is paired with
+/// same destruction. Implicit linear system of each stage is solved by
+/// Gauss-Seidel iterations.
+///
+/// # Panics
+///
+/// [`run`](OdeSolver::run) panics if any of the following occurs.
+///
+/// * Run time is negative or NaN (backward integration does not keep
+///   positivity).
+/// * Gauss-Seidel iterations of a stage do not converge in 10000
+///   iterations. Then, decrease step size.
+///
+/// This is implemented for scalar components ([`f32`] and [`f64`]).
+///
+/// [modified Patankar-Runge-Kutta methods]: https://doi.org/10.1016/S0168-9274(02)00239-8
+pub struct Patankar<'a, T, F> {
+    /// Step size.
+    h: T,
+
+    /// `true` if second order method is used.
+    second_order: bool,
+
+    /// Time at the start of run.
+    time: T,
+
+    /// Old value.
+    old_value: VArr<F>,
+
+    /// New value.
+    new_value: VArr<F>,
+
+    /// Production-destruction closure.
+    pd_slope: Rc<PdSlope<'a, T, F>>,
+
+    /// Work for terms of stages.
+    terms: [Vec<(usize, usize, F)>; 2],
+
+    /// Work for stage value.
+    stage: VArr<F>,
+
+    /// Work for linear system.
+    system: System<F>,
+}
+
+impl<'a, T, F> Patankar<'a, T, F>
+where
+    T: Time,
+    F: Copy + Default,
+{
+    /// Creates a new instance with MPRK22 method.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `h` is zero or negative or NaN or infinity.
+    #[must_use]
+    pub fn new(h: T) -> Box<Self> {
+        Self::with_order(h, true)
+    }
+
+    /// Creates a new instance with modified Patankar-Euler method.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `h` is zero or negative or NaN or infinity.
+    #[must_use]
+    pub fn euler(h: T) -> Box<Self> {
+        Self::with_order(h, false)
+    }
+
+    /// Sets production-destruction closure of this instance.
+    pub fn set_pd_slope(&mut self, value: Rc<PdSlope<'a, T, F>>) {
+        self.pd_slope = value;
+    }
+
+    /// Creates a new instance with order flag.
+    fn with_order(h: T, second_order: bool) -> Box<Self> {
+        assert!(!h.is_nan());
+        assert!(!h.is_infinite());
+        assert!(h > T::zero());
+        Box::new(Self {
+            h,
+            second_order,
+            time: T::zero(),
+            old_value: Default::default(),
+            new_value: Default::default(),
+            pd_slope: Rc::new(|terms, _, _| terms.clear()),
+            terms: Default::default(),
+            stage: Default::default(),
+            system: Default::default(),
+        })
+    }
+}
+
+/// Linear system of Patankar stage.
+#[derive(Default)]
+struct System<F> {
+    /// Diagonal elements.
+    diag: Vec<F>,
+
+    /// Right-hand side.
+    rhs: Vec<F>,
+
+    /// Off-diagonal elements `(row, col, -value)` sorted by rows.
+    offs: Vec<(usize, usize, F)>,
+}
+
+macro_rules! impl_patankar {
+    ($ty:ty) => {
+        impl<T> Patankar<'_, T, $ty>
+        where
+            T: Time,
+        {
+            /// Advance step.
+            fn step(&mut self, x: T, h: T) {
+                let hf = h.as_f64() as $ty;
+                let [terms0, terms1] = &mut self.terms;
+                terms0.clear();
+                (self.pd_slope)(terms0, x, &self.old_value);
+
+                let old_value = self.old_value.as_ref();
+                self.system
+                    .build(old_value, old_value, &[(terms0.as_slice(), hf)]);
+                self.system.solve(self.new_value.as_mut());
+
+                if self.second_order {
+                    self.stage.clone_from(&self.new_value);
+                    terms1.clear();
+                    (self.pd_slope)(terms1, x + h, &self.stage);
+
+                    let stage = self.stage.as_ref();
+                    let parts = [(terms0.as_slice(), hf / 2.0), (terms1.as_slice(), hf / 2.0)];
+                    self.system.build(old_value, stage, &parts);
+                    self.system.solve(self.new_value.as_mut());
+                }
+
+                self.old_value.clone_from(&self.new_value);
+            }
+        }
+
+        impl System<$ty> {
+            /// Maximum count of Gauss-Seidel iterations.
+            const MAX_ITERS: usize = 10000;
+
+            /// Builds system from terms scaled by step sizes.
+            ///
+            /// Terms are weighted by `sigma` (Patankar weights).
+            fn build(
+                &mut self,
+                value: &[$ty],
+                sigma: &[$ty],
+                parts: &[(&[(usize, usize, $ty)], $ty)],
+            ) {
+                self.diag.clear();
+                self.diag.resize(value.len(), 1.0);
+                self.rhs.clear();
+                self.rhs.extend_from_slice(value);
+                self.offs.clear();
+
+                for &(terms, h) in parts {
+                    for &(idx, weight_idx, r) in terms {
+                        if r < 0.0 && sigma[idx] > 0.0 {
+                            self.diag[idx] -= h * r / sigma[idx];
+                        } else if r > 0.0 && weight_idx == idx {
+                            self.rhs[idx] += h * r;
+                        } else if r > 0.0 && sigma[weight_idx] > 0.0 {
+                            self.offs.push((idx, weight_idx, h * r / sigma[weight_idx]));
+                        }
+                    }
+                }
+
+                self.offs.sort_unstable_by_key(|&(row, col, _)| (row, col));
+            }
+
+            /// Solves system, and writes solution to `result`.
+            ///
+            /// # Panics
+            ///
+            /// Panics if iterations do not converge.
+            fn solve(&self, result: &mut Vec<$ty>) {
+                result.clear();
+                result.extend_from_slice(&self.rhs);
+                for (idx, x) in result.iter_mut().enumerate() {
+                    *x /= self.diag[idx];
+                }
+
+                if self.offs.is_empty() {
+                    return;
+                }
+
+                for iter in 0.. {
+                    assert!(iter < Self::MAX_ITERS, "{}", msg::NOT_CONVERGED);
+                    let mut change = 0.0 as $ty;
+                    let mut scale = 0.0 as $ty;
+                    let mut offs = self.offs.iter().peekable();
+                    for idx in 0..result.len() {
+                        let mut sum = self.rhs[idx];
+                        while let Some(&(_, col, a)) = offs.next_if(|x| x.0 == idx) {
+                            sum += a * result[col];
+                        }
+
+                        let x = sum / self.diag[idx];
+                        change = change.max((x - result[idx]).abs());
+                        scale = scale.max(x.abs());
+                        result[idx] = x;
+                    }
+
+                    if change <= scale * <$ty>::EPSILON * 4.0 {
+                        break;
+                    }
+                }
+            }
+        }
+
+        impl<'a, T> OdeSolver<'a, T, VArr<$ty>> for Patankar<'a, T, $ty>
+        where
+            T: Time,
+            $ty: std::ops::MulAssign<T>,
+        {
+            fn new_value(&self) -> &VArr<$ty> {
+                &self.new_value
+            }
+
+            fn set_value(&mut self, value: &VArr<$ty>) {
+                self.old_value.clone_from(value);
+                self.new_value.clone_from(value);
+                self.stage.clone_from(value);
+            }
+
+            fn time(&self) -> T {
+                self.time
+            }
+
+            fn set_time(&mut self, value: T) {
+                self.time = value;
+            }
+
+            fn run(&mut self, t: T) {
+                assert!(t >= T::zero(), "{}", msg::NEGATIVE_TIME);
+                let h = self.h;
+                let t0 = self.time;
+                let mut step = |x, h| self.step(t0 + x, h);
+                ode_util::run_steps(t, h, &mut step);
+                self.time = t0 + t;
+            }
+        }
+    };
+}
+
+impl_patankar!(f32);
+impl_patankar!(f64);
+
+mod msg {
+    pub const NOT_CONVERGED: &str = "Gauss-Seidel iterations of Patankar stage did not converge.";
+    pub const NEGATIVE_TIME: &str = "Patankar solver run time must be non-negative.";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[should_panic(expected = "Patankar solver run time must be non-negative.")]
+    fn run_rejects_negative_time() {
+        let mut solver = Patankar::<f64, f64>::new(0.1);
+        solver.set_value(&VArr::new(vec![1.0]));
+        solver.run(-1.0);
+    }
+}
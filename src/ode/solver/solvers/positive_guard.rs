@@ -0,0 +1,167 @@
+//! Provider of [`PositiveGuard`].
+
+use crate::ode::solver::OdeSolver;
+use crate::ode::values::{Positivity, RF32, Time, Value};
+use std::ops::MulAssign;
+
+/// ODE solver wrapper with step rejection for negative values.
+///
+/// This runs inner solver by chunks of step size. If negative component
+/// appears after chunk, the chunk is rejected and retried with half step
+/// size. After accepted chunk, step size is doubled again (up to original
+/// step size). If step size reaches minimum step size, chunk is accepted
+/// and negative components are clamped to zero. (NaN components are kept,
+/// so divergence of inner solver is not hidden.)
+///
+/// Inner solver step size should be not less than step size of this type.
+pub struct PositiveGuard<'a, T, V> {
+    /// Inner solver.
+    solver: Box<dyn OdeSolver<'a, T, V> + 'a>,
+
+    /// Step size.
+    h: T,
+
+    /// Minimum step size.
+    min_h: T,
+
+    /// Time at the start of run.
+    time: T,
+
+    /// Current value.
+    value: V,
+
+    /// Count of rejected chunks.
+    rejections: usize,
+}
+
+impl<'a, T, V> PositiveGuard<'a, T, V>
+where
+    T: Time,
+    V: Value + MulAssign<T> + Positivity,
+{
+    /// Creates a new instance.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the following occurs.
+    ///
+    /// * `h` is zero or negative or NaN or infinity.
+    /// * `min_h` is zero or negative or NaN or greater than `h`.
+    #[must_use]
+    pub fn new(solver: Box<dyn OdeSolver<'a, T, V> + 'a>, h: T, min_h: T) -> Box<Self> {
+        assert!(!h.is_nan());
+        assert!(!h.is_infinite());
+        assert!(h > T::zero());
+        assert!(!min_h.is_nan());
+        assert!(min_h > T::zero());
+        assert!(min_h <= h);
+        Box::new(Self {
+            solver,
+            h,
+            min_h,
+            time: T::zero(),
+            value: Default::default(),
+            rejections: 0,
+        })
+    }
+
+    /// Returns count of rejected chunks.
+    pub fn rejections(&self) -> usize {
+        self.rejections
+    }
+
+    /// Runs inner solver by chunk, and returns `true` if it is accepted.
+    fn try_chunk(&mut self, x: T, h: T) -> bool {
+        self.solver.set_time(self.time + x);
+        self.solver.set_value(&self.value);
+        self.solver.run(h);
+
+        let new_value = self.solver.new_value();
+        let accepted = new_value.is_non_negative();
+        if accepted || h.abs() <= self.min_h {
+            self.value.clone_from(new_value);
+            self.value.clamp_negative();
+            return true;
+        }
+
+        self.rejections += 1;
+        false
+    }
+}
+
+impl<'a, T, V> OdeSolver<'a, T, V> for PositiveGuard<'a, T, V>
+where
+    T: Time,
+    V: Value + MulAssign<T> + Positivity,
+{
+    fn new_value(&self) -> &V {
+        &self.value
+    }
+
+    fn set_value(&mut self, value: &V) {
+        self.value.clone_from(value);
+    }
+
+    fn time(&self) -> T {
+        self.time
+    }
+
+    fn set_time(&mut self, value: T) {
+        self.time = value;
+    }
+
+    fn run(&mut self, t: T) {
+        assert!(!t.is_nan());
+        assert!(!t.is_infinite());
+
+        let mut x = T::zero();
+        let mut h = self.h;
+        while x.abs() < t.abs() {
+            let size = (t - x).abs().min(h).unwrap_or(h).copysign(t);
+            if self.try_chunk(x, size) {
+                x = x + size;
+                h = (h * RF32(2.0)).min(self.h).unwrap_or(self.h);
+            } else {
+                h = h / RF32(2.0);
+            }
+        }
+
+        self.time = self.time + t;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ode::solver::GpOdeSolver;
+    use crate::ode::solver::solvers::Euler;
+    use std::rc::Rc;
+
+    #[test]
+    fn run_keeps_values_non_negative() {
+        // Euler step of `dx/dt = -10 x` with `h = 0.5` overshoots to `-4 x`.
+        let mut inner = Euler::new(0.5);
+        inner.set_slope(Rc::new(|grad: &mut f64, _: f64, value: &f64| {
+            *grad = -10.0 * value
+        }));
+        let mut solver = PositiveGuard::new(inner, 0.5, 1e-3);
+        solver.set_value(&1.0);
+        solver.run(2.0);
+
+        assert!(*solver.new_value() >= 0.0);
+        assert!(*solver.new_value() < 1e-3);
+        assert!(solver.rejections() > 0);
+        assert_eq!(solver.time(), 2.0);
+    }
+
+    #[test]
+    fn run_keeps_nan_at_minimum_step_size() {
+        let mut inner = Euler::new(0.5);
+        inner.set_slope(Rc::new(|grad: &mut f64, _: f64, _: &f64| *grad = f64::NAN));
+        let mut solver = PositiveGuard::new(inner, 0.5, 0.125);
+        solver.set_value(&1.0);
+        solver.run(0.5);
+
+        assert!(solver.new_value().is_nan());
+    }
+}
@@ -14,6 +14,9 @@ pub struct RungeKutta<'a, T, V> {
     /// Step size.
     h: T,
 
+    /// Time at the start of run.
+    time: T,
+
     /// Old value.
     old_value: V,
 
@@ -21,7 +24,7 @@ pub struct RungeKutta<'a, T, V> {
     new_value: V,
 
     /// Slope closure.
-    slope: Rc<Slope<'a, V>>,
+    slope: Rc<Slope<'a, T, V>>,
 
     /// Work for general.
     work: V,
@@ -50,6 +53,7 @@ where
         assert!(h > T::zero());
         Box::new(Self {
             h,
+            time: T::zero(),
             old_value: Default::default(),
             new_value: Default::default(),
             slope: ode_util::flat_slope(),
@@ -60,18 +64,18 @@ where
     }
 
     /// Advance step.
-    fn step(&mut self, h: T, slope: Rc<Slope<V>>) {
+    fn step(&mut self, x: T, h: T, slope: Rc<Slope<T, V>>) {
         assert!(!h.is_nan());
 
-        self.step0(slope.clone());
-        self.step1(slope.clone(), h);
-        self.step2(slope.clone(), h);
-        self.step3(slope.clone(), h);
+        self.step0(slope.clone(), x);
+        self.step1(slope.clone(), x, h);
+        self.step2(slope.clone(), x, h);
+        self.step3(slope.clone(), x, h);
 
-        self.grads[0] *= h * RF32(1.0 / 6.0);
-        self.grads[1] *= h * RF32(2.0 / 6.0);
-        self.grads[2] *= h * RF32(2.0 / 6.0);
-        self.grads[3] *= h * RF32(1.0 / 6.0);
+        self.grads[0] *= h / RF32(6.0);
+        self.grads[1] *= h / RF32(3.0);
+        self.grads[2] *= h / RF32(3.0);
+        self.grads[3] *= h / RF32(6.0);
         self.work.fill_zero();
         self.work += &self.grads[0];
         self.work += &self.grads[1];
@@ -80,43 +84,44 @@ where
         self.new_value.fill_zero();
         self.new_value += &self.old_value;
         self.new_value += &self.work;
+        self.old_value.clone_from(&self.new_value);
     }
 
     /// Calculate step 0.
-    fn step0(&mut self, slope: Rc<Slope<V>>) {
+    fn step0(&mut self, slope: Rc<Slope<T, V>>, x: T) {
         self.points[0].clone_from(&self.old_value);
-        slope(&mut self.grads[0], &self.points[0]);
+        slope(&mut self.grads[0], x, &self.points[0]);
     }
 
     /// Calculate step 1.
-    fn step1(&mut self, slope: Rc<Slope<V>>, h: T) {
+    fn step1(&mut self, slope: Rc<Slope<T, V>>, x: T, h: T) {
         let (points, rest) = self.points.split_at_mut(1);
         let dy = WorkOn(&mut self.work)
             .set(&self.grads[0])
             .calc(|w| *w *= h / RF32(2.0));
-        rest[0] += &points[0];
+        rest[0].clone_from(&points[0]);
         rest[0] += dy;
-        slope(&mut self.grads[1], &mut rest[0]);
+        slope(&mut self.grads[1], x + h / RF32(2.0), &rest[0]);
     }
 
     /// Calculate step 2.
-    fn step2(&mut self, slope: Rc<Slope<V>>, h: T) {
+    fn step2(&mut self, slope: Rc<Slope<T, V>>, x: T, h: T) {
         let (points, rest) = self.points.split_at_mut(2);
         let dy = WorkOn(&mut self.work)
             .set(&self.grads[1])
             .calc(|w| *w *= h / RF32(2.0));
-        rest[0] += &points[0];
+        rest[0].clone_from(&points[0]);
         rest[0] += dy;
-        slope(&mut self.grads[2], &mut rest[0]);
+        slope(&mut self.grads[2], x + h / RF32(2.0), &rest[0]);
     }
 
     /// Calculate step 3.
-    fn step3(&mut self, slope: Rc<Slope<V>>, h: T) {
+    fn step3(&mut self, slope: Rc<Slope<T, V>>, x: T, h: T) {
         let (points, rest) = self.points.split_at_mut(3);
         let dy = WorkOn(&mut self.work).set(&self.grads[2]).calc(|w| *w *= h);
-        rest[0] += &points[0];
+        rest[0].clone_from(&points[0]);
         rest[0] += dy;
-        slope(&mut self.grads[3], &mut rest[0]);
+        slope(&mut self.grads[3], x + h, &rest[0]);
     }
 }
 
@@ -137,10 +142,20 @@ where
         self.grads.iter_mut().for_each(|x| x.clone_zero(value));
     }
 
+    fn time(&self) -> T {
+        self.time
+    }
+
+    fn set_time(&mut self, value: T) {
+        self.time = value;
+    }
+
     fn run(&mut self, t: T) {
         let h = self.h;
-        let mut step = |h| self.step(h, self.slope.clone());
+        let t0 = self.time;
+        let mut step = |x, h| self.step(t0 + x, h, self.slope.clone());
         ode_util::run_steps(t, h, &mut step);
+        self.time = t0 + t;
     }
 }
 
@@ -149,7 +164,50 @@ where
     T: Time,
     V: Value + MulAssign<T>,
 {
-    fn set_slope(&mut self, value: Rc<Slope<'a, V>>) {
+    fn set_slope(&mut self, value: Rc<Slope<'a, T, V>>) {
         self.slope = value;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    #[test]
+    fn run_advances_all_steps() {
+        let mut solver = RungeKutta::new(0.1);
+        solver.set_slope(Rc::new(|grad: &mut f64, _: f64, value: &f64| {
+            *grad = -value
+        }));
+        solver.set_value(&1.0);
+        solver.run(1.0);
+
+        let expected = (-1.0_f64).exp();
+        assert!((solver.new_value() - expected).abs() < 1e-6);
+    }
+
+    #[test]
+    fn run_passes_stage_times() {
+        let times = Rc::new(RefCell::new(Vec::new()));
+        let mut solver = RungeKutta::new(0.1);
+        let stage_times = times.clone();
+        solver.set_slope(Rc::new(move |grad: &mut f64, t: f64, _: &f64| {
+            stage_times.borrow_mut().push(t);
+            *grad = t;
+        }));
+        solver.set_time(2.0);
+        solver.set_value(&0.0);
+        solver.run(0.2);
+
+        let expected = [2.0, 2.05, 2.05, 2.1, 2.1, 2.15, 2.15, 2.2];
+        let times = times.borrow();
+        assert_eq!(times.len(), expected.len());
+        for (t, expected) in times.iter().zip(expected) {
+            assert!((t - expected).abs() < 1e-12);
+        }
+
+        assert!((solver.time() - 2.2).abs() < 1e-12);
+        assert!((solver.new_value() - 0.42).abs() < 1e-12);
+    }
+}
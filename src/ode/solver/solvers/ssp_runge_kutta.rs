@@ -0,0 +1,166 @@
+//! Provider of [`SspRungeKutta`].
+
+use crate::ode::solver::{GpOdeSolver, OdeSolver};
+use crate::ode::values::{RF32, Time, Value};
+use crate::ode::{Slope, ode_util};
+use std::ops::MulAssign;
+use std::rc::Rc;
+
+/// ODE solver by [strong stability preserving Runge-Kutta method].
+///
+/// This is third order method of Shu and Osher. Each stage is convex
+/// combination of Euler steps. So, if Euler step with same step size keeps
+/// values non-negative (like network diffusion with `h * degree <= 1`),
+/// this method also keeps them non-negative.
+///
+/// [strong stability preserving Runge-Kutta method]: https://en.wikipedia.org/wiki/Runge%E2%80%93Kutta_methods#Strong_stability_preserving_Runge%E2%80%93Kutta_methods
+pub struct SspRungeKutta<'a, T, V> {
+    /// Step size.
+    h: T,
+
+    /// Time at the start of run.
+    time: T,
+
+    /// Old value.
+    old_value: V,
+
+    /// New value.
+    new_value: V,
+
+    /// Slope closure.
+    slope: Rc<Slope<'a, T, V>>,
+
+    /// Work for stage value.
+    stage: V,
+
+    /// Work for gradient.
+    grad: V,
+}
+
+impl<T, V> SspRungeKutta<'_, T, V>
+where
+    T: Time,
+    V: Value + MulAssign<T>,
+{
+    /// Creates a new instance.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `h` is zero or negative or NaN or infinity.
+    #[must_use]
+    pub fn new(h: T) -> Box<Self> {
+        assert!(!h.is_nan());
+        assert!(!h.is_infinite());
+        assert!(h > T::zero());
+        Box::new(Self {
+            h,
+            time: T::zero(),
+            old_value: Default::default(),
+            new_value: Default::default(),
+            slope: ode_util::flat_slope(),
+            stage: Default::default(),
+            grad: Default::default(),
+        })
+    }
+
+    /// Advance step.
+    fn step(&mut self, x: T, h: T, slope: Rc<Slope<T, V>>) {
+        assert!(!h.is_nan());
+
+        // u1 = u + h * f(u)
+        self.stage.clone_from(&self.old_value);
+        self.euler_step(&*slope, x, h);
+
+        // u2 = 3/4 * u + 1/4 * (u1 + h * f(u1))
+        self.euler_step(&*slope, x + h, h);
+        self.mix(RF32(3.0), RF32(4.0));
+
+        // u3 = 1/3 * u + 2/3 * (u2 + h * f(u2))
+        self.euler_step(&*slope, x + h / RF32(2.0), h);
+        self.stage *= RF32(2.0);
+        self.mix(RF32(1.0), RF32(3.0));
+
+        self.new_value.clone_from(&self.stage);
+        self.old_value.clone_from(&self.new_value);
+    }
+
+    /// Advances stage value by Euler step.
+    fn euler_step(&mut self, slope: &Slope<T, V>, x: T, h: T) {
+        slope(&mut self.grad, x, &self.stage);
+        self.grad *= h;
+        self.stage += &self.grad;
+    }
+
+    /// Sets stage value to `(old_value * a + stage) / b`.
+    fn mix(&mut self, a: RF32, b: RF32) {
+        self.grad.clone_from(&self.old_value);
+        self.grad *= a;
+        self.stage += &self.grad;
+        self.stage /= b;
+    }
+}
+
+impl<'a, T, V> OdeSolver<'a, T, V> for SspRungeKutta<'a, T, V>
+where
+    T: Time,
+    V: Value + MulAssign<T>,
+{
+    fn new_value(&self) -> &V {
+        &self.new_value
+    }
+
+    fn set_value(&mut self, value: &V) {
+        self.old_value.clone_from(value);
+        self.new_value.clone_zero(value);
+        self.stage.clone_zero(value);
+        self.grad.clone_zero(value);
+    }
+
+    fn time(&self) -> T {
+        self.time
+    }
+
+    fn set_time(&mut self, value: T) {
+        self.time = value;
+    }
+
+    fn run(&mut self, t: T) {
+        let h = self.h;
+        let t0 = self.time;
+        let mut step = |x, h| self.step(t0 + x, h, self.slope.clone());
+        ode_util::run_steps(t, h, &mut step);
+        self.time = t0 + t;
+    }
+}
+
+impl<'a, T, V> GpOdeSolver<'a, T, V> for SspRungeKutta<'a, T, V>
+where
+    T: Time,
+    V: Value + MulAssign<T>,
+{
+    fn set_slope(&mut self, value: Rc<Slope<'a, T, V>>) {
+        self.slope = value;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns error of `dx/dt = t * x` solved by step size `h`.
+    fn error(h: f64) -> f64 {
+        let mut solver = SspRungeKutta::new(h);
+        solver.set_slope(Rc::new(|grad: &mut f64, t: f64, value: &f64| {
+            *grad = t * value
+        }));
+        solver.set_value(&1.0);
+        solver.run(1.0);
+        (solver.new_value() - 0.5_f64.exp()).abs()
+    }
+
+    #[test]
+    fn run_is_third_order() {
+        let order = (error(0.1) / error(0.05)).log2();
+        assert!((order - 3.0).abs() < 0.2);
+    }
+}
@@ -0,0 +1,160 @@
+//! Provider of [`Complex`].
+
+use crate::ode::values::{Norm, RF32, RF64};
+use std::ops::{AddAssign, DivAssign, Mul, MulAssign, SubAssign};
+
+/// Complex number value.
+///
+/// This is value for wave function of node (like quantum walk). Real
+/// scalars (including simulation time) scale both real and imaginary parts.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Complex<F> {
+    /// Real part.
+    pub re: F,
+
+    /// Imaginary part.
+    pub im: F,
+}
+
+impl<F> Complex<F> {
+    /// Creates a new instance.
+    pub fn new(re: F, im: F) -> Self {
+        Self { re, im }
+    }
+}
+
+impl<F> From<(F, F)> for Complex<F> {
+    fn from(value: (F, F)) -> Self {
+        Self::new(value.0, value.1)
+    }
+}
+
+impl<F> From<Complex<F>> for (F, F) {
+    fn from(value: Complex<F>) -> Self {
+        (value.re, value.im)
+    }
+}
+
+impl<F> MulAssign<RF32> for Complex<F>
+where
+    F: MulAssign<RF32>,
+{
+    fn mul_assign(&mut self, rhs: RF32) {
+        self.re *= rhs;
+        self.im *= rhs;
+    }
+}
+
+impl<F> DivAssign<RF32> for Complex<F>
+where
+    F: DivAssign<RF32>,
+{
+    fn div_assign(&mut self, rhs: RF32) {
+        self.re /= rhs;
+        self.im /= rhs;
+    }
+}
+
+impl<F> MulAssign<RF64> for Complex<F>
+where
+    F: MulAssign<RF64>,
+{
+    fn mul_assign(&mut self, rhs: RF64) {
+        self.re *= rhs;
+        self.im *= rhs;
+    }
+}
+
+impl<F> DivAssign<RF64> for Complex<F>
+where
+    F: DivAssign<RF64>,
+{
+    fn div_assign(&mut self, rhs: RF64) {
+        self.re /= rhs;
+        self.im /= rhs;
+    }
+}
+
+impl<F> AddAssign<&Self> for Complex<F>
+where
+    F: AddAssign + Copy,
+{
+    fn add_assign(&mut self, rhs: &Self) {
+        self.re += rhs.re;
+        self.im += rhs.im;
+    }
+}
+
+impl<F> SubAssign<&Self> for Complex<F>
+where
+    F: SubAssign + Copy,
+{
+    fn sub_assign(&mut self, rhs: &Self) {
+        self.re -= rhs.re;
+        self.im -= rhs.im;
+    }
+}
+
+impl<F> Norm for Complex<F>
+where
+    F: Norm,
+{
+    fn norm_sqr(&self) -> f64 {
+        self.re.norm_sqr() + self.im.norm_sqr()
+    }
+
+    fn dot(&self, other: &Self) -> f64 {
+        self.re.dot(&other.re) + self.im.dot(&other.im)
+    }
+}
+
+macro_rules! impl_complex {
+    ($ty:ty) => {
+        impl Complex<$ty> {
+            /// Imaginary unit.
+            pub const I: Self = Self { re: 0.0, im: 1.0 };
+
+            /// Returns complex conjugate.
+            pub fn conj(self) -> Self {
+                Self::new(self.re, -self.im)
+            }
+
+            /// Returns `self * i` (rotation by right angle).
+            pub fn mul_i(self) -> Self {
+                Self::new(-self.im, self.re)
+            }
+        }
+
+        impl From<$ty> for Complex<$ty> {
+            fn from(value: $ty) -> Self {
+                Self::new(value, 0.0)
+            }
+        }
+
+        impl Mul for Complex<$ty> {
+            type Output = Self;
+
+            fn mul(self, rhs: Self) -> Self::Output {
+                let re = self.re * rhs.re - self.im * rhs.im;
+                let im = self.re * rhs.im + self.im * rhs.re;
+                Self::new(re, im)
+            }
+        }
+
+        impl MulAssign for Complex<$ty> {
+            fn mul_assign(&mut self, rhs: Self) {
+                *self = *self * rhs;
+            }
+        }
+
+        impl MulAssign<$ty> for Complex<$ty> {
+            fn mul_assign(&mut self, rhs: $ty) {
+                self.re *= rhs;
+                self.im *= rhs;
+            }
+        }
+    };
+}
+
+impl_complex!(f32);
+impl_complex!(f64);
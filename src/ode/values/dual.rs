@@ -0,0 +1,179 @@
+//! Provider of [`Dual`].
+
+use crate::ode::values::{RF32, RF64, Tangents};
+use std::ops::{AddAssign, DivAssign, Mul, MulAssign, SubAssign};
+
+/// Dual number value with `N` tangent components.
+///
+/// This is value for forward sensitivity analysis. Primal part is usual node
+/// value, and tangent component `k` is its derivative by parameter `k`.
+/// Arithmetic operators follow rules of derivative (like product rule). So,
+/// tangents are propagated through any explicit solver.
+///
+/// For derivatives by initial values, set tangent of initial node values
+/// (like `eps[k] = 1.0` for node `k`). For derivatives by edge weights, see
+/// [`NdeqSim::set_sensitivity`](crate::net_ode::NdeqSim::set_sensitivity).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Dual<F, const N: usize> {
+    /// Primal part.
+    pub re: F,
+
+    /// Tangent components.
+    pub eps: [F; N],
+}
+
+impl<F, const N: usize> Dual<F, N> {
+    /// Creates a new instance.
+    pub fn new(re: F, eps: [F; N]) -> Self {
+        Self { re, eps }
+    }
+}
+
+impl<F, const N: usize> Default for Dual<F, N>
+where
+    F: Copy + Default,
+{
+    fn default() -> Self {
+        Self::new(F::default(), [F::default(); N])
+    }
+}
+
+impl<F, const N: usize> MulAssign<RF32> for Dual<F, N>
+where
+    F: MulAssign<RF32>,
+{
+    fn mul_assign(&mut self, rhs: RF32) {
+        self.re *= rhs;
+        self.eps.iter_mut().for_each(|x| *x *= rhs);
+    }
+}
+
+impl<F, const N: usize> DivAssign<RF32> for Dual<F, N>
+where
+    F: DivAssign<RF32>,
+{
+    fn div_assign(&mut self, rhs: RF32) {
+        self.re /= rhs;
+        self.eps.iter_mut().for_each(|x| *x /= rhs);
+    }
+}
+
+impl<F, const N: usize> MulAssign<RF64> for Dual<F, N>
+where
+    F: MulAssign<RF64>,
+{
+    fn mul_assign(&mut self, rhs: RF64) {
+        self.re *= rhs;
+        self.eps.iter_mut().for_each(|x| *x *= rhs);
+    }
+}
+
+impl<F, const N: usize> DivAssign<RF64> for Dual<F, N>
+where
+    F: DivAssign<RF64>,
+{
+    fn div_assign(&mut self, rhs: RF64) {
+        self.re /= rhs;
+        self.eps.iter_mut().for_each(|x| *x /= rhs);
+    }
+}
+
+impl<F, const N: usize> AddAssign<&Self> for Dual<F, N>
+where
+    F: AddAssign + Copy,
+{
+    fn add_assign(&mut self, rhs: &Self) {
+        self.re += rhs.re;
+        for (x, y) in self.eps.iter_mut().zip(rhs.eps) {
+            *x += y;
+        }
+    }
+}
+
+impl<F, const N: usize> SubAssign<&Self> for Dual<F, N>
+where
+    F: SubAssign + Copy,
+{
+    fn sub_assign(&mut self, rhs: &Self) {
+        self.re -= rhs.re;
+        for (x, y) in self.eps.iter_mut().zip(rhs.eps) {
+            *x -= y;
+        }
+    }
+}
+
+impl<F, const N: usize> Tangents for Dual<F, N>
+where
+    F: AddAssign + Copy,
+{
+    type Scalar = F;
+
+    fn tangents_len(&self) -> usize {
+        N
+    }
+
+    fn tangent(&self, k: usize) -> Self::Scalar {
+        self.eps[k]
+    }
+
+    fn add_tangent(&mut self, k: usize, src: &Self) {
+        self.eps[k] += src.re;
+    }
+}
+
+macro_rules! impl_dual {
+    ($ty:ty) => {
+        impl<const N: usize> Dual<$ty, N> {
+            /// Creates a constant (all tangents are zero).
+            pub fn constant(re: $ty) -> Self {
+                Self::new(re, [0.0; N])
+            }
+
+            /// Creates a variable of parameter `k` (tangent `k` is one).
+            ///
+            /// # Panics
+            ///
+            /// Panics if `k` is out of tangent components count.
+            pub fn variable(re: $ty, k: usize) -> Self {
+                let mut ret = Self::constant(re);
+                ret.eps[k] = 1.0;
+                ret
+            }
+        }
+
+        impl<const N: usize> From<$ty> for Dual<$ty, N> {
+            fn from(value: $ty) -> Self {
+                Self::constant(value)
+            }
+        }
+
+        impl<const N: usize> Mul for Dual<$ty, N> {
+            type Output = Self;
+
+            fn mul(self, rhs: Self) -> Self::Output {
+                let mut ret = Self::constant(self.re * rhs.re);
+                for k in 0..N {
+                    ret.eps[k] = self.re * rhs.eps[k] + self.eps[k] * rhs.re;
+                }
+
+                ret
+            }
+        }
+
+        impl<const N: usize> MulAssign for Dual<$ty, N> {
+            fn mul_assign(&mut self, rhs: Self) {
+                *self = *self * rhs;
+            }
+        }
+
+        impl<const N: usize> MulAssign<$ty> for Dual<$ty, N> {
+            fn mul_assign(&mut self, rhs: $ty) {
+                self.re *= rhs;
+                self.eps.iter_mut().for_each(|x| *x *= rhs);
+            }
+        }
+    };
+}
+
+impl_dual!(f32);
+impl_dual!(f64);
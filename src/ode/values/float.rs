@@ -18,6 +18,9 @@ pub trait Float:
     /// Converts self into [`f32`].
     fn as_f32(self) -> f32;
 
+    /// Converts self into [`f64`].
+    fn as_f64(self) -> f64;
+
     /// Returns this number with the sign equal to `sign`.
     fn copysign(self, sign: Self) -> Self;
 
@@ -61,6 +64,10 @@ impl Float for f32 {
         self
     }
 
+    fn as_f64(self) -> f64 {
+        f64::from(self)
+    }
+
     fn copysign(self, sign: Self) -> Self {
         self.copysign(sign)
     }
@@ -71,6 +78,10 @@ impl Float for f64 {
         self as f32
     }
 
+    fn as_f64(self) -> f64 {
+        self
+    }
+
     fn copysign(self, sign: Self) -> Self {
         self.copysign(sign)
     }
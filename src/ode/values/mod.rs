@@ -1,13 +1,33 @@
 //! Values for ODE.
 
+pub use complex::*;
+pub use dual::*;
 pub use float::*;
+pub use norm::*;
+pub use positivity::*;
 pub use rf32::*;
+pub use rf64::*;
+pub use tangents::*;
 pub use time::*;
+pub use tuples::*;
 pub use value::*;
 pub use varr::*;
+pub use vec_n::*;
 
+#[cfg(feature = "derive")]
+pub use ndeq_derive::Value;
+
+mod complex;
+mod dual;
 mod float;
+mod norm;
+mod positivity;
 mod rf32;
+mod rf64;
+mod rhs_ops;
+mod tangents;
 mod time;
+mod tuples;
 mod value;
 mod varr;
+mod vec_n;
@@ -0,0 +1,55 @@
+//! Provider of [`Norm`].
+
+use crate::ode::values::VArr;
+
+/// Euclidean norm of value.
+///
+/// This is used by implicit solvers to check convergence, and by quantum
+/// walks to check norm conservation. Norm of vector value is calculated
+/// from norms of its components.
+pub trait Norm {
+    /// Returns squared norm.
+    fn norm_sqr(&self) -> f64;
+
+    /// Returns real inner product (real part of it for complex value).
+    ///
+    /// This is used by Krylov subspace solvers. `x.dot(x)` equals squared
+    /// norm of `x`.
+    fn dot(&self, other: &Self) -> f64;
+
+    /// Returns norm.
+    fn norm(&self) -> f64 {
+        self.norm_sqr().sqrt()
+    }
+}
+
+macro_rules! impl_norm {
+    ($ty:ty) => {
+        impl Norm for $ty {
+            fn norm_sqr(&self) -> f64 {
+                f64::from(*self) * f64::from(*self)
+            }
+
+            fn dot(&self, other: &Self) -> f64 {
+                f64::from(*self) * f64::from(*other)
+            }
+        }
+    };
+}
+
+impl_norm!(f32);
+impl_norm!(f64);
+
+impl<T> Norm for VArr<T>
+where
+    T: Norm,
+{
+    fn norm_sqr(&self) -> f64 {
+        self.as_ref().iter().map(|x| x.norm_sqr()).sum()
+    }
+
+    fn dot(&self, other: &Self) -> f64 {
+        let pairs = self.as_ref().iter().zip(other.as_ref());
+        pairs.map(|(x, y)| x.dot(y)).sum()
+    }
+}
@@ -0,0 +1,47 @@
+//! Provider of [`Positivity`].
+
+use crate::ode::values::VArr;
+
+/// Sign check of value components.
+///
+/// This is used by positivity-preserving solvers. Vector value is
+/// non-negative if all of its components are non-negative.
+pub trait Positivity {
+    /// Returns `true` if all components are non-negative.
+    fn is_non_negative(&self) -> bool;
+
+    /// Replaces negative components with zero (NaN components are kept).
+    fn clamp_negative(&mut self);
+}
+
+macro_rules! impl_positivity {
+    ($ty:ty) => {
+        impl Positivity for $ty {
+            fn is_non_negative(&self) -> bool {
+                *self >= 0.0
+            }
+
+            fn clamp_negative(&mut self) {
+                if *self < 0.0 {
+                    *self = 0.0;
+                }
+            }
+        }
+    };
+}
+
+impl_positivity!(f32);
+impl_positivity!(f64);
+
+impl<T> Positivity for VArr<T>
+where
+    T: Positivity,
+{
+    fn is_non_negative(&self) -> bool {
+        self.as_ref().iter().all(|x| x.is_non_negative())
+    }
+
+    fn clamp_negative(&mut self) {
+        self.as_mut().iter_mut().for_each(|x| x.clamp_negative());
+    }
+}
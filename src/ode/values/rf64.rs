@@ -0,0 +1,15 @@
+//! Provider of [`RF64`].
+
+use crate::ode::values::rhs_ops::def_rhs_ops;
+
+/// Newtype of `f64` for operator right-hand side.
+///
+/// This is double-precision version of [`RF32`](crate::ode::values::RF32).
+/// Left-hand side can be `f32` or `f64`, and the result type is aligned to
+/// the left-hand side (so, `f64` value keeps its precision, and `f32` value
+/// is calculated with rounded right-hand side).
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+pub struct RF64(pub f64);
+
+def_rhs_ops!(RF64, f32, |x| x as f32);
+def_rhs_ops!(RF64, f64, |x| x);
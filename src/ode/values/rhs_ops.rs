@@ -0,0 +1,70 @@
+//! Provider of operators for right-hand side newtypes.
+//!
+//! [`def_rhs_ops`] implements arithmetic operators (and compound assignment
+//! operators) of floating point type with right-hand side newtype such as
+//! [`RF32`](crate::ode::values::RF32) and [`RF64`](crate::ode::values::RF64).
+
+/// Implements operators of `$ty` with right-hand side `$rf`.
+///
+/// `$conv` converts inner value `$x` of `$rf` to `$ty`.
+macro_rules! def_rhs_ops {
+    ($rf:ident, $ty:ty, |$x:ident| $conv:expr) => {
+        def_rhs_ops!(@op $rf, $ty, Add, add, AddAssign, add_assign, +, +=, |$x| $conv);
+        def_rhs_ops!(@op $rf, $ty, Sub, sub, SubAssign, sub_assign, -, -=, |$x| $conv);
+        def_rhs_ops!(@op $rf, $ty, Mul, mul, MulAssign, mul_assign, *, *=, |$x| $conv);
+        def_rhs_ops!(@op $rf, $ty, Div, div, DivAssign, div_assign, /, /=, |$x| $conv);
+    };
+    (
+        @op $rf:ident, $ty:ty,
+        $op:ident, $op_fn:ident, $assign:ident, $assign_fn:ident, $tok:tt, $assign_tok:tt,
+        |$x:ident| $conv:expr
+    ) => {
+        impl std::ops::$op<$rf> for $ty {
+            type Output = $ty;
+            fn $op_fn(self, rhs: $rf) -> Self::Output {
+                let $x = rhs.0;
+                self $tok $conv
+            }
+        }
+
+        impl std::ops::$op<$rf> for &$ty {
+            type Output = $ty;
+            fn $op_fn(self, rhs: $rf) -> Self::Output {
+                let $x = rhs.0;
+                self $tok $conv
+            }
+        }
+
+        impl std::ops::$op<&$rf> for $ty {
+            type Output = $ty;
+            fn $op_fn(self, rhs: &$rf) -> Self::Output {
+                let $x = rhs.0;
+                self $tok $conv
+            }
+        }
+
+        impl std::ops::$op<&$rf> for &$ty {
+            type Output = $ty;
+            fn $op_fn(self, rhs: &$rf) -> Self::Output {
+                let $x = rhs.0;
+                self $tok $conv
+            }
+        }
+
+        impl std::ops::$assign<$rf> for $ty {
+            fn $assign_fn(&mut self, rhs: $rf) {
+                let $x = rhs.0;
+                *self $assign_tok $conv;
+            }
+        }
+
+        impl std::ops::$assign<&$rf> for $ty {
+            fn $assign_fn(&mut self, rhs: &$rf) {
+                let $x = rhs.0;
+                *self $assign_tok $conv;
+            }
+        }
+    };
+}
+
+pub(crate) use def_rhs_ops;
@@ -0,0 +1,28 @@
+//! Provider of [`Tangents`].
+
+/// Value with tangent components.
+///
+/// This is used by forward sensitivity analysis (see
+/// [`NdeqSim::set_sensitivity`](crate::net_ode::NdeqSim::set_sensitivity)).
+/// Tangent component `k` is derivative of primal part by parameter `k`.
+pub trait Tangents {
+    /// Scalar type of tangent component.
+    type Scalar;
+
+    /// Returns tangent components count.
+    fn tangents_len(&self) -> usize;
+
+    /// Returns tangent component `k`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is out of tangent components count.
+    fn tangent(&self, k: usize) -> Self::Scalar;
+
+    /// Adds primal part of `src` to tangent component `k`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is out of tangent components count.
+    fn add_tangent(&mut self, k: usize, src: &Self);
+}
@@ -0,0 +1,134 @@
+//! Provider of tuple values ([`Tuple2`], [`Tuple3`] and [`Tuple4`]).
+
+use crate::ode::values::{Norm, Positivity};
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+macro_rules! def_tuple {
+    ($(#[$attr:meta])* $name:ident; $($ty:ident $idx:tt),+) => {
+        $(#[$attr])*
+        ///
+        /// Arithmetic operators work field-wise. So, if all fields are values,
+        /// this is also value.
+        #[derive(Clone, Copy, Debug, Default, PartialEq)]
+        pub struct $name<$($ty),+>($(pub $ty),+);
+
+        impl<$($ty),+> From<($($ty),+)> for $name<$($ty),+> {
+            fn from(value: ($($ty),+)) -> Self {
+                Self($(value.$idx),+)
+            }
+        }
+
+        impl<$($ty),+> From<$name<$($ty),+>> for ($($ty),+) {
+            fn from(value: $name<$($ty),+>) -> Self {
+                ($(value.$idx),+)
+            }
+        }
+
+        impl<U, $($ty),+> MulAssign<U> for $name<$($ty),+>
+        where
+            U: Copy,
+            $($ty: MulAssign<U>),+
+        {
+            fn mul_assign(&mut self, rhs: U) {
+                $(self.$idx *= rhs;)+
+            }
+        }
+
+        impl<U, $($ty),+> DivAssign<U> for $name<$($ty),+>
+        where
+            U: Copy,
+            $($ty: DivAssign<U>),+
+        {
+            fn div_assign(&mut self, rhs: U) {
+                $(self.$idx /= rhs;)+
+            }
+        }
+
+        impl<$($ty),+> AddAssign<&Self> for $name<$($ty),+>
+        where
+            $($ty: for<'a> AddAssign<&'a $ty>),+
+        {
+            fn add_assign(&mut self, rhs: &Self) {
+                $(self.$idx += &rhs.$idx;)+
+            }
+        }
+
+        impl<$($ty),+> SubAssign<&Self> for $name<$($ty),+>
+        where
+            $($ty: for<'a> SubAssign<&'a $ty>),+
+        {
+            fn sub_assign(&mut self, rhs: &Self) {
+                $(self.$idx -= &rhs.$idx;)+
+            }
+        }
+
+        impl<$($ty),+> Positivity for $name<$($ty),+>
+        where
+            $($ty: Positivity),+
+        {
+            fn is_non_negative(&self) -> bool {
+                true $(&& self.$idx.is_non_negative())+
+            }
+
+            fn clamp_negative(&mut self) {
+                $(self.$idx.clamp_negative();)+
+            }
+        }
+
+        impl<$($ty),+> Norm for $name<$($ty),+>
+        where
+            $($ty: Norm),+
+        {
+            fn norm_sqr(&self) -> f64 {
+                0.0 $(+ self.$idx.norm_sqr())+
+            }
+
+            fn dot(&self, other: &Self) -> f64 {
+                0.0 $(+ self.$idx.dot(&other.$idx))+
+            }
+        }
+    };
+}
+
+def_tuple!(
+    /// Tuple value with two fields.
+    Tuple2; A 0, B 1
+);
+
+def_tuple!(
+    /// Tuple value with three fields.
+    Tuple3; A 0, B 1, C 2
+);
+
+def_tuple!(
+    /// Tuple value with four fields.
+    Tuple4; A 0, B 1, C 2, D 3
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net_ode::NdeqSim;
+    use crate::net_ode::solver::adapters::NetRungeKutta;
+    use crate::ode::values::VecN;
+    use crate::util::TestNet;
+
+    #[test]
+    fn sim_diffuses_each_field() {
+        let values = vec![
+            Tuple2(4.0, VecN::new([1.0, 0.0])),
+            Tuple2(0.0, VecN::new([0.0, 2.0])),
+            Tuple2(2.0, VecN::new([2.0, 1.0])),
+        ];
+        let net = TestNet::<Tuple2<f64, VecN<f64, 2>>>::new(values, vec![(0, 1, 1.0), (1, 2, 1.0)]);
+        let solver = NetRungeKutta::new(0.01);
+        let mut sim = NdeqSim::new(&net, &solver);
+        sim.run(20.0);
+
+        for Tuple2(x, y) in net.values() {
+            assert!((x - 2.0).abs() < 1e-6);
+            assert!((y[0] - 1.0).abs() < 1e-6);
+            assert!((y[1] - 1.0).abs() < 1e-6);
+        }
+    }
+}
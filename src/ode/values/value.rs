@@ -1,6 +1,6 @@
 //! Provider of [`Value`].
 
-use crate::ode::values::RF32;
+use crate::ode::values::{RF32, RF64};
 use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
 
 /// Value (function value of ODE system).
@@ -8,6 +8,19 @@ use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
 /// # Value type
 /// 
 /// This value can be a vector as well as a scalar.
+///
+/// # Scalar types
+///
+/// Value is scaled by [`RF32`] or [`RF64`]. Double-precision coefficients
+/// (like normalized weights, capacities and sources) use [`RF64`], so `f64`
+/// values keep precision.
+///
+/// # Custom types
+///
+/// This trait is implemented for all types with required operators. So, user
+/// types must implement `MulAssign` and `DivAssign` with both [`RF32`] and
+/// [`RF64`] (types implementing only [`RF32`] ones are no longer values).
+/// With `derive` feature, `#[derive(Value)]` implements them field-wise.
 pub trait Value:
     'static
     + Clone
@@ -15,6 +28,8 @@ pub trait Value:
     + PartialEq
     + MulAssign<RF32>
     + DivAssign<RF32>
+    + MulAssign<RF64>
+    + DivAssign<RF64>
     + for<'a> AddAssign<&'a Self>
     + for<'a> SubAssign<&'a Self>
 {
@@ -38,6 +53,8 @@ where
         + PartialEq
         + MulAssign<RF32>
         + DivAssign<RF32>
+        + MulAssign<RF64>
+        + DivAssign<RF64>
         + for<'a> AddAssign<&'a Self>
         + for<'a> SubAssign<&'a Self>,
 {
@@ -15,6 +15,11 @@ impl<T> VArr<T> {
         Self(x)
     }
 
+    /// Converts this into vector.
+    pub fn into_vec(self) -> Vec<T> {
+        self.0
+    }
+
     /// Returns the number of elements.
     pub fn len(&self) -> usize {
         self.0.len()
@@ -0,0 +1,166 @@
+//! Provider of [`VecN`].
+
+use crate::ode::values::{Norm, Positivity, RF32, RF64};
+use std::ops::{AddAssign, DivAssign, Index, IndexMut, MulAssign, SubAssign};
+
+/// Fixed-size vector value.
+///
+/// This is small vector for multi-component node (like concentrations of
+/// multiple species). Arithmetic operators work component-wise.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VecN<F, const N: usize>(pub [F; N]);
+
+impl<F, const N: usize> VecN<F, N> {
+    /// Creates a new instance.
+    pub fn new(x: [F; N]) -> Self {
+        Self(x)
+    }
+
+    /// Returns components count.
+    pub fn len(&self) -> usize {
+        N
+    }
+
+    /// Returns `true` if `self` has no components.
+    pub fn is_empty(&self) -> bool {
+        N == 0
+    }
+
+    /// Returns iterator of components.
+    pub fn iter(&self) -> impl Iterator<Item = &F> {
+        self.0.iter()
+    }
+}
+
+impl<F, const N: usize> Default for VecN<F, N>
+where
+    F: Copy + Default,
+{
+    fn default() -> Self {
+        Self([F::default(); N])
+    }
+}
+
+impl<F, const N: usize> From<[F; N]> for VecN<F, N> {
+    fn from(value: [F; N]) -> Self {
+        Self(value)
+    }
+}
+
+impl<F, const N: usize> From<VecN<F, N>> for [F; N] {
+    fn from(value: VecN<F, N>) -> Self {
+        value.0
+    }
+}
+
+impl<F, const N: usize> Index<usize> for VecN<F, N> {
+    type Output = F;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.0[index]
+    }
+}
+
+impl<F, const N: usize> IndexMut<usize> for VecN<F, N> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.0[index]
+    }
+}
+
+impl<F, const N: usize> MulAssign<RF32> for VecN<F, N>
+where
+    F: MulAssign<RF32>,
+{
+    fn mul_assign(&mut self, rhs: RF32) {
+        self.0.iter_mut().for_each(|x| *x *= rhs);
+    }
+}
+
+impl<F, const N: usize> DivAssign<RF32> for VecN<F, N>
+where
+    F: DivAssign<RF32>,
+{
+    fn div_assign(&mut self, rhs: RF32) {
+        self.0.iter_mut().for_each(|x| *x /= rhs);
+    }
+}
+
+impl<F, const N: usize> MulAssign<RF64> for VecN<F, N>
+where
+    F: MulAssign<RF64>,
+{
+    fn mul_assign(&mut self, rhs: RF64) {
+        self.0.iter_mut().for_each(|x| *x *= rhs);
+    }
+}
+
+impl<F, const N: usize> DivAssign<RF64> for VecN<F, N>
+where
+    F: DivAssign<RF64>,
+{
+    fn div_assign(&mut self, rhs: RF64) {
+        self.0.iter_mut().for_each(|x| *x /= rhs);
+    }
+}
+
+impl<F, const N: usize> AddAssign<&Self> for VecN<F, N>
+where
+    F: AddAssign + Copy,
+{
+    fn add_assign(&mut self, rhs: &Self) {
+        for (x, y) in self.0.iter_mut().zip(rhs.0) {
+            *x += y;
+        }
+    }
+}
+
+impl<F, const N: usize> SubAssign<&Self> for VecN<F, N>
+where
+    F: SubAssign + Copy,
+{
+    fn sub_assign(&mut self, rhs: &Self) {
+        for (x, y) in self.0.iter_mut().zip(rhs.0) {
+            *x -= y;
+        }
+    }
+}
+
+impl<F, const N: usize> Positivity for VecN<F, N>
+where
+    F: Positivity,
+{
+    fn is_non_negative(&self) -> bool {
+        self.0.iter().all(|x| x.is_non_negative())
+    }
+
+    fn clamp_negative(&mut self) {
+        self.0.iter_mut().for_each(|x| x.clamp_negative());
+    }
+}
+
+impl<F, const N: usize> Norm for VecN<F, N>
+where
+    F: Norm,
+{
+    fn norm_sqr(&self) -> f64 {
+        self.0.iter().map(|x| x.norm_sqr()).sum()
+    }
+
+    fn dot(&self, other: &Self) -> f64 {
+        self.0.iter().zip(&other.0).map(|(x, y)| x.dot(y)).sum()
+    }
+}
+
+macro_rules! impl_mul_assign {
+    ($ty:ty) => {
+        impl<const N: usize> MulAssign<$ty> for VecN<$ty, N> {
+            fn mul_assign(&mut self, rhs: $ty) {
+                self.0.iter_mut().for_each(|x| *x *= rhs);
+            }
+        }
+    };
+}
+
+impl_mul_assign!(f32);
+impl_mul_assign!(f64);
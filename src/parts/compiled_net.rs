@@ -0,0 +1,477 @@
+//! Provider of [`CompiledNet`].
+
+use crate::ode::Slope;
+use crate::ode::values::{RF64, VArr, Value};
+use crate::parts::{EdgeMode, FlowLaw, Laplacian, NdeqNet, Weight};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Compiled network.
+///
+/// This type freezes topology of target network into compressed sparse row
+/// arrays (with precomputed node degrees and capacities). Degrees are also
+/// used for normalized [`Laplacian`] variants. Then, its slope works without
+/// dynamic iterators and allocations. Node values are still imported and
+/// exported through target network.
+///
+/// If target network is time-varying, compiled edge weights are updated for
+/// each time given to slope.
+///
+/// Compiled edges are normalized to [`EdgeMode::InFlow`] edges. So, edge mode
+/// of this type is always in-flow, regardless of target network edge mode.
+///
+/// If topology of target network is changed, call [`refresh`](NdeqNet::refresh)
+/// (or simply use [`NdeqSim`](crate::net_ode::NdeqSim), it calls this
+/// automatically).
+pub struct CompiledNet<'a, V, W = f32> {
+    /// Target network.
+    net: &'a dyn NdeqNet<V, W>,
+
+    /// Compiled topology.
+    csr: RefCell<Csr<W>>,
+}
+
+impl<'a, V, W> CompiledNet<'a, V, W>
+where
+    V: Value,
+    W: Weight,
+{
+    /// Creates a new instance.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the following occurs.
+    ///
+    /// * `net` edges or capacities have node index out of nodes count.
+    /// * `net` capacity is not positive or not finite.
+    /// * `net` capacity of same node is returned more than once.
+    /// * `net` or its nodes are currently borrowed.
+    pub fn new(net: &'a dyn NdeqNet<V, W>) -> Self {
+        let ret = Self {
+            net,
+            csr: Default::default(),
+        };
+
+        ret.refresh();
+        ret
+    }
+
+    /// Returns target network.
+    pub fn net(&self) -> &'a dyn NdeqNet<V, W> {
+        self.net
+    }
+
+    /// Returns nodes count.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is currently mutably borrowed.
+    pub fn len(&self) -> usize {
+        self.csr.borrow().degrees.len()
+    }
+
+    /// Returns `true` if `self` has no nodes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is currently mutably borrowed.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns capacity of node.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the following occurs.
+    ///
+    /// * `idx` is out of nodes count.
+    /// * `self` is currently mutably borrowed.
+    pub fn capacity(&self, idx: usize) -> f64 {
+        self.csr.borrow().capacities[idx]
+    }
+
+    /// Returns degree (sum of in-flow edge weights) of node.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the following occurs.
+    ///
+    /// * `idx` is out of nodes count.
+    /// * `self` is currently mutably borrowed.
+    pub fn degree(&self, idx: usize) -> f64 {
+        self.csr.borrow().degrees[idx]
+    }
+}
+
+impl<V, W> CompiledNet<'_, V, W>
+where
+    V: Value,
+    W: Weight,
+{
+    /// Updates edge weights to time `t` if target network is time-varying.
+    fn sync_time(&self, t: f64) {
+        let csr = self.csr.borrow();
+        if !csr.time_varying || csr.time == Some(t) {
+            return;
+        }
+
+        drop(csr);
+        let mode = self.net.edge_mode();
+        let edges = self.net.edges_at(t).enumerate();
+        let edges = edges.flat_map(|(idx, edge)| in_flow_edges(idx, edge, mode));
+        let mut csr = self.csr.borrow_mut();
+        csr.update_weights(edges);
+        csr.time = Some(t);
+    }
+}
+
+impl<V, W> NdeqNet<V, W> for CompiledNet<'_, V, W>
+where
+    V: Value,
+    W: Weight,
+{
+    fn edges(&self) -> Box<dyn Iterator<Item = (usize, usize, W)> + '_> {
+        let csr = self.csr.borrow();
+        let (mut bwd_idx, mut pos) = (0, 0);
+        Box::new(std::iter::from_fn(move || {
+            if pos == csr.targets.len() {
+                return None;
+            }
+
+            while csr.offsets[bwd_idx + 1] <= pos {
+                bwd_idx += 1;
+            }
+
+            pos += 1;
+            Some((bwd_idx, csr.targets[pos - 1], csr.weights[pos - 1]))
+        }))
+    }
+
+    fn edges_at(&self, t: f64) -> Box<dyn Iterator<Item = (usize, usize, W)> + '_> {
+        self.sync_time(t);
+        self.edges()
+    }
+
+    fn is_time_varying(&self) -> bool {
+        self.net.is_time_varying()
+    }
+
+    fn fixed_nodes(&self) -> Box<dyn Iterator<Item = usize> + '_> {
+        self.net.fixed_nodes()
+    }
+
+    fn capacities(&self) -> Box<dyn Iterator<Item = (usize, f64)> + '_> {
+        self.net.capacities()
+    }
+
+    fn sources(&self) -> Box<dyn Iterator<Item = (usize, V)> + '_> {
+        self.net.sources()
+    }
+
+    fn advections(&self) -> Box<dyn Iterator<Item = (usize, usize, f64)> + '_> {
+        self.net.advections()
+    }
+
+    fn edge_mode(&self) -> EdgeMode {
+        EdgeMode::InFlow
+    }
+
+    fn laplacian(&self) -> Laplacian {
+        self.net.laplacian()
+    }
+
+    fn import_values(&self, values: &[V]) {
+        self.net.import_values(values);
+    }
+
+    fn export_values(&self, values: &mut Vec<V>) {
+        self.net.export_values(values);
+    }
+
+    fn export_ids(&self, ids: &mut Vec<usize>) {
+        self.net.export_ids(ids);
+    }
+
+    fn nodes_count(&self) -> usize {
+        self.net.nodes_count()
+    }
+
+    fn topology_hash(&self) -> u64 {
+        self.net.topology_hash()
+    }
+
+    fn refresh(&self) {
+        let len = self.net.nodes_count();
+        let mode = self.net.edge_mode();
+        let edges = self.net.edges().enumerate();
+        let edges = edges.flat_map(|(idx, edge)| in_flow_edges(idx, edge, mode));
+        let mut csr = self.csr.borrow_mut();
+        csr.compile(len, edges);
+        csr.set_capacities(self.net.capacities());
+        csr.time_varying = self.net.is_time_varying();
+        csr.time = None;
+    }
+
+    fn target(&self) -> Option<&dyn NdeqNet<V, W>> {
+        Some(self.net)
+    }
+
+    fn slope(&self) -> Rc<Slope<'_, f64, VArr<V>>> {
+        let laplacian = self.net.laplacian();
+        let scaled = RefCell::new(VArr::default());
+        let work = RefCell::new(V::default());
+        Rc::new(move |result, t, value| {
+            self.sync_time(t);
+            let csr = self.csr.borrow();
+            let mut scaled = scaled.borrow_mut();
+            let mut work = work.borrow_mut();
+            let value = laplacian.scale_values(value, &csr.degrees, &mut scaled);
+            result.fill_zero();
+
+            for bwd_idx in 0..csr.degrees.len() {
+                for (fwd_idx, w) in csr.row(bwd_idx) {
+                    work.clone_from(&value[fwd_idx]);
+                    w.scale(&mut *work);
+                    result[bwd_idx] += &work;
+                }
+
+                work.clone_from(&value[bwd_idx]);
+                *work *= RF64(csr.degrees[bwd_idx]);
+                result[bwd_idx] -= &work;
+            }
+
+            laplacian.scale_result(result, &csr.degrees);
+            csr.apply_capacities(result);
+        })
+    }
+
+    fn flow_slope<'s>(&'s self, law: Rc<dyn FlowLaw<V, W> + 's>) -> Rc<Slope<'s, f64, VArr<V>>> {
+        let laplacian = self.net.laplacian();
+        let scaled = RefCell::new(VArr::default());
+        let work = RefCell::new(V::default());
+        Rc::new(move |result, t, value| {
+            self.sync_time(t);
+            let csr = self.csr.borrow();
+            let mut scaled = scaled.borrow_mut();
+            let mut work = work.borrow_mut();
+            let value = laplacian.scale_values(value, &csr.degrees, &mut scaled);
+            result.fill_zero();
+
+            for bwd_idx in 0..csr.degrees.len() {
+                for (idx, fwd_idx, w) in csr.row_edges(bwd_idx) {
+                    law.edge_flow(&mut work, idx, w, &value[bwd_idx], &value[fwd_idx]);
+                    result[bwd_idx] += &work;
+                }
+            }
+
+            laplacian.scale_result(result, &csr.degrees);
+            csr.apply_capacities(result);
+        })
+    }
+
+    fn pd_terms(
+        &self,
+        result: &mut Vec<(usize, usize, V)>,
+        t: f64,
+        value: &VArr<V>,
+        law: Option<&dyn FlowLaw<V, W>>,
+    ) {
+        self.net.pd_terms(result, t, value, law);
+    }
+}
+
+/// Returns in-flow edges (with edge index `idx`) equivalent to edge of given mode.
+fn in_flow_edges<W: Weight>(
+    idx: usize,
+    edge: (usize, usize, W),
+    mode: EdgeMode,
+) -> impl Iterator<Item = (usize, usize, usize, W)> {
+    let (bwd_idx, fwd_idx, w) = edge;
+    let edges = match mode {
+        EdgeMode::Undirected => [Some((bwd_idx, fwd_idx)), Some((fwd_idx, bwd_idx))],
+        EdgeMode::InFlow => [Some((bwd_idx, fwd_idx)), None],
+        EdgeMode::OutFlow => [Some((fwd_idx, bwd_idx)), None],
+    };
+
+    edges
+        .into_iter()
+        .flatten()
+        .map(move |(b, f)| (idx, b, f, w))
+}
+
+/// Compressed sparse row arrays.
+#[derive(Default)]
+struct Csr<W> {
+    /// Row start positions (length is nodes count + 1).
+    offsets: Vec<usize>,
+
+    /// Forward node indices.
+    targets: Vec<usize>,
+
+    /// Edge weights.
+    weights: Vec<W>,
+
+    /// Edge indices of target network.
+    edge_idxs: Vec<usize>,
+
+    /// Node degrees.
+    degrees: Vec<f64>,
+
+    /// Node capacities.
+    capacities: Vec<f64>,
+
+    /// Positions of edges in compile order.
+    positions: Vec<usize>,
+
+    /// `true` if edge weights are varied by time.
+    time_varying: bool,
+
+    /// Time of edge weights.
+    time: Option<f64>,
+}
+
+impl<W> Csr<W>
+where
+    W: Weight,
+{
+    /// Rebuilds arrays from edges.
+    fn compile(&mut self, len: usize, edges: impl Iterator<Item = (usize, usize, usize, W)>) {
+        let edges = edges.collect::<Vec<_>>();
+
+        self.offsets.clear();
+        self.offsets.resize(len + 1, 0);
+        for &(_, bwd_idx, fwd_idx, _) in &edges {
+            assert!(bwd_idx < len, "{}", msg::IDX_OUT_OF_RANGE);
+            assert!(fwd_idx < len, "{}", msg::IDX_OUT_OF_RANGE);
+            self.offsets[bwd_idx + 1] += 1;
+        }
+
+        for i in 0..len {
+            self.offsets[i + 1] += self.offsets[i];
+        }
+
+        let mut cursors = self.offsets[..len].to_vec();
+        self.targets.clear();
+        self.targets.resize(edges.len(), 0);
+        self.weights.clear();
+        self.weights.resize(edges.len(), W::default());
+        self.edge_idxs.clear();
+        self.edge_idxs.resize(edges.len(), 0);
+        self.degrees.clear();
+        self.degrees.resize(len, 0.0);
+        self.positions.clear();
+        for (idx, bwd_idx, fwd_idx, w) in edges {
+            let pos = cursors[bwd_idx];
+            self.targets[pos] = fwd_idx;
+            self.weights[pos] = w;
+            self.edge_idxs[pos] = idx;
+            self.degrees[bwd_idx] += w.to_f64();
+            self.positions.push(pos);
+            cursors[bwd_idx] += 1;
+        }
+    }
+
+    /// Updates edge weights with edges in compile order.
+    fn update_weights(&mut self, edges: impl Iterator<Item = (usize, usize, usize, W)>) {
+        for (pos, (_, _, _, w)) in self.positions.iter().zip(edges) {
+            self.weights[*pos] = w;
+        }
+
+        for idx in 0..self.degrees.len() {
+            self.degrees[idx] = self.row(idx).map(|(_, w)| w.to_f64()).sum();
+        }
+    }
+
+    /// Sets node capacities (unspecified nodes are `1.0`).
+    fn set_capacities(&mut self, capacities: impl Iterator<Item = (usize, f64)>) {
+        let mut specified = vec![false; self.degrees.len()];
+        self.capacities.clear();
+        self.capacities.resize(self.degrees.len(), 1.0);
+        for (idx, c) in capacities {
+            assert!(idx < specified.len(), "{}", msg::CAPACITY_OUT_OF_RANGE);
+            assert!(c > 0.0 && c.is_finite(), "{}", msg::INVALID_CAPACITY);
+            assert!(!specified[idx], "{}", msg::DUPLICATE_CAPACITY);
+            specified[idx] = true;
+            self.capacities[idx] = c;
+        }
+    }
+
+    /// Divides slope of nodes by their capacities.
+    fn apply_capacities<V: Value>(&self, result: &mut VArr<V>) {
+        for (idx, &c) in self.capacities.iter().enumerate() {
+            if c != 1.0 {
+                result[idx] /= RF64(c);
+            }
+        }
+    }
+
+    /// Returns edges of row.
+    fn row(&self, idx: usize) -> impl Iterator<Item = (usize, W)> + '_ {
+        let range = self.offsets[idx]..self.offsets[idx + 1];
+        let targets = self.targets[range.clone()].iter();
+        let weights = self.weights[range].iter();
+        targets.copied().zip(weights.copied())
+    }
+
+    /// Returns edges of row with edge indices of target network.
+    fn row_edges(&self, idx: usize) -> impl Iterator<Item = (usize, usize, W)> + '_ {
+        let range = self.offsets[idx]..self.offsets[idx + 1];
+        let edge_idxs = self.edge_idxs[range.clone()].iter().copied();
+        edge_idxs.zip(self.row(idx)).map(|(k, (j, w))| (k, j, w))
+    }
+}
+
+mod msg {
+    pub const IDX_OUT_OF_RANGE: &str = "Edge node index is out of nodes count.";
+    pub const CAPACITY_OUT_OF_RANGE: &str = "Capacity node index is out of nodes count.";
+    pub const INVALID_CAPACITY: &str = "Node capacity must be positive and finite.";
+    pub const DUPLICATE_CAPACITY: &str = "Node capacity is specified more than once.";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::TestNet;
+
+    #[test]
+    fn slope_matches_target_network() {
+        for net in TestNet::variants() {
+            let compiled = CompiledNet::new(&net);
+            let value = VArr::new(net.values());
+            let mut expected = value.clone();
+            let mut actual = value.clone();
+            net.slope()(&mut expected, 0.0, &value);
+            compiled.slope()(&mut actual, 0.0, &value);
+
+            assert_eq!(expected.len(), actual.len());
+            for idx in 0..value.len() {
+                assert!((expected[idx] - actual[idx]).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn edges_are_in_flow_rows() {
+        let net = TestNet::new(vec![0.0_f64; 3], vec![(0, 1, 0.5_f64), (2, 1, 2.0)]);
+        let compiled = CompiledNet::new(&net);
+        let edges = compiled.edges().collect::<Vec<_>>();
+        assert_eq!(edges, [(0, 1, 0.5), (1, 0, 0.5), (1, 2, 2.0), (2, 1, 2.0)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Capacity node index is out of nodes count.")]
+    fn out_of_range_capacity_panics() {
+        let mut net = TestNet::new(vec![1.0_f64, 2.0], vec![(0, 1, 1.0_f64)]);
+        net.capacities = vec![(2, 2.0)];
+        CompiledNet::new(&net);
+    }
+
+    #[test]
+    #[should_panic(expected = "Node capacity is specified more than once.")]
+    fn duplicate_capacity_panics() {
+        let mut net = TestNet::new(vec![1.0_f64, 2.0], vec![(0, 1, 1.0_f64)]);
+        net.capacities = vec![(1, 2.0), (1, 2.0)];
+        CompiledNet::new(&net);
+    }
+}
@@ -0,0 +1,27 @@
+//! Provider of [`EdgeMode`].
+
+/// Edge mode of network.
+///
+/// This type decides how each edge `(bwd_idx, fwd_idx, w)` of network moves
+/// node values. In each case, flow of edge is `w * (fwd_value - bwd_value)`.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum EdgeMode {
+    /// Undirected edge.
+    ///
+    /// Backward node gains flow, and forward node loses same flow. So, total
+    /// of node values is conserved. Each edge should be listed only once.
+    Undirected,
+
+    /// Directed in-flow edge.
+    ///
+    /// Only backward node gains flow (backward node is pulled to forward
+    /// node). For undirected diffusion, each edge should be listed twice in
+    /// both directions.
+    #[default]
+    InFlow,
+
+    /// Directed out-flow edge.
+    ///
+    /// Only forward node loses flow (forward node is pulled to backward node).
+    OutFlow,
+}
@@ -0,0 +1,48 @@
+//! Provider of [`FlowLaw`].
+
+use crate::parts::Weight;
+
+/// Flow law of network edges.
+///
+/// This trait calculates flow of edge from node values. Default flow law of
+/// network is linear law `w * (fwd_value - bwd_value)`. Other laws can be
+/// used with [`NdeqNet::flow_slope`](crate::parts::NdeqNet::flow_slope).
+/// Type parameter `W` is edge weight type (see [`Weight`]).
+///
+/// # Antisymmetry
+///
+/// Flow law should be antisymmetric (flow from `x` to `y` is negative of
+/// flow from `y` to `x`). Otherwise, total of node values is not conserved
+/// and [`CompiledNet`](crate::parts::CompiledNet) can not normalize edges.
+///
+/// # Closures
+///
+/// Closures with same signature as [`flow`](Self::flow) are also flow laws.
+pub trait FlowLaw<V, W = f32>
+where
+    W: Weight,
+{
+    /// Calculates flow to backward node, and writes it to `result`.
+    fn flow(&self, result: &mut V, w: W, bwd_value: &V, fwd_value: &V);
+
+    /// Calculates flow of edge to backward node, and writes it to `result`.
+    ///
+    /// `idx` is index of edge in [`NdeqNet::edges`](crate::parts::NdeqNet::edges)
+    /// order of target network. This is for flow laws with per-edge
+    /// coefficients. Network slopes call this instead of [`flow`](Self::flow).
+    /// Default implementation calls [`flow`](Self::flow).
+    fn edge_flow(&self, result: &mut V, idx: usize, w: W, bwd_value: &V, fwd_value: &V) {
+        let _ = idx;
+        self.flow(result, w, bwd_value, fwd_value);
+    }
+}
+
+impl<V, W, F> FlowLaw<V, W> for F
+where
+    W: Weight,
+    F: Fn(&mut V, W, &V, &V),
+{
+    fn flow(&self, result: &mut V, w: W, bwd_value: &V, fwd_value: &V) {
+        self(result, w, bwd_value, fwd_value)
+    }
+}
@@ -0,0 +1,59 @@
+//! Provider of [`BoundedConfidence`].
+
+use crate::parts::{FlowLaw, Weight};
+
+/// Bounded confidence flow law (like opinion dynamics).
+///
+/// Flow is `w * d` if `|d|` is less than threshold, otherwise zero
+/// (`d` is `fwd_value - bwd_value`).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BoundedConfidence {
+    /// Threshold.
+    threshold: f64,
+}
+
+impl BoundedConfidence {
+    /// Creates a new instance.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `threshold` is negative or NaN or infinity.
+    pub fn new(threshold: f64) -> Self {
+        assert!(
+            threshold.is_finite() && threshold >= 0.0,
+            "{}",
+            msg::INVALID_THRESHOLD
+        );
+        Self { threshold }
+    }
+
+    /// Returns threshold.
+    pub fn threshold(&self) -> f64 {
+        self.threshold
+    }
+}
+
+macro_rules! impl_flow_law {
+    ($ty:ty) => {
+        impl<W> FlowLaw<$ty, W> for BoundedConfidence
+        where
+            W: Weight,
+        {
+            fn flow(&self, result: &mut $ty, w: W, bwd_value: &$ty, fwd_value: &$ty) {
+                let w = w.to_f64() as $ty;
+                let d = fwd_value - bwd_value;
+                *result = match d.abs() < self.threshold as $ty {
+                    true => w * d,
+                    false => 0.0,
+                };
+            }
+        }
+    };
+}
+
+impl_flow_law!(f32);
+impl_flow_law!(f64);
+
+mod msg {
+    pub const INVALID_THRESHOLD: &str = "Threshold must be non-negative and finite.";
+}
@@ -0,0 +1,156 @@
+//! Provider of [`CrossDiffusion`].
+
+use crate::ode::values::VecN;
+use crate::parts::{FlowLaw, Weight};
+
+/// Linear flow law with diffusion matrix.
+///
+/// Flow of component `k` is `w * sum_l(m_kl * d_l)` (`d` is `fwd_value -
+/// bwd_value` and `m` is matrix of edge). So, difference of one species can
+/// drive flow of other species (cross-diffusion), and coupling can vary by
+/// edge.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CrossDiffusion<const N: usize> {
+    /// Matrices `m` (row-major) of edges (single item is shared by all edges).
+    matrices: Vec<[[f64; N]; N]>,
+}
+
+impl<const N: usize> CrossDiffusion<N> {
+    /// Creates a new instance with matrix shared by all edges.
+    pub fn new(matrix: [[f64; N]; N]) -> Self {
+        Self {
+            matrices: vec![matrix],
+        }
+    }
+
+    /// Creates a new instance with matrix of each edge.
+    ///
+    /// Item `i` of `matrices` is matrix of edge `i` in
+    /// [`NdeqNet::edges`](crate::parts::NdeqNet::edges) order. So,
+    /// [`flow`](FlowLaw::flow) (without edge index) panics, and network must
+    /// not have more edges than `matrices`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `matrices` is empty.
+    pub fn per_edge(matrices: Vec<[[f64; N]; N]>) -> Self {
+        assert!(!matrices.is_empty(), "{}", msg::NO_MATRICES);
+        Self { matrices }
+    }
+
+    /// Returns matrices of edges (single item is shared by all edges).
+    pub fn matrices(&self) -> &[[[f64; N]; N]] {
+        &self.matrices
+    }
+
+    /// Returns matrix of edge `idx` (`None` for unknown edge).
+    fn edge_matrix(&self, idx: Option<usize>) -> &[[f64; N]; N] {
+        match self.matrices.len() {
+            1 => &self.matrices[0],
+            _ => &self.matrices[idx.expect(msg::NO_EDGE_IDX)],
+        }
+    }
+}
+
+/// Flow calculation with optional edge index.
+///
+/// [`FlowLaw::flow`] and [`FlowLaw::edge_flow`] share this. So, they give
+/// same flow for shared coefficients.
+trait IndexedFlow<V> {
+    /// Calculates flow of edge `idx` (`None` for unknown edge).
+    fn indexed_flow(
+        &self,
+        result: &mut V,
+        idx: Option<usize>,
+        w: f64,
+        bwd_value: &V,
+        fwd_value: &V,
+    );
+}
+
+macro_rules! impl_flow_law {
+    ($ty:ty) => {
+        impl<const N: usize> IndexedFlow<VecN<$ty, N>> for CrossDiffusion<N> {
+            fn indexed_flow(
+                &self,
+                result: &mut VecN<$ty, N>,
+                idx: Option<usize>,
+                w: f64,
+                bwd_value: &VecN<$ty, N>,
+                fwd_value: &VecN<$ty, N>,
+            ) {
+                let w = w as $ty;
+                let matrix = self.edge_matrix(idx);
+                for k in 0..N {
+                    let row = matrix[k].iter();
+                    let diffs = fwd_value.iter().zip(bwd_value.iter());
+                    let sum = row.zip(diffs).map(|(&m, (f, b))| m as $ty * (f - b));
+                    result[k] = w * sum.sum::<$ty>();
+                }
+            }
+        }
+
+        impl<const N: usize, W> FlowLaw<VecN<$ty, N>, W> for CrossDiffusion<N>
+        where
+            W: Weight,
+        {
+            fn flow(
+                &self,
+                result: &mut VecN<$ty, N>,
+                w: W,
+                bwd_value: &VecN<$ty, N>,
+                fwd_value: &VecN<$ty, N>,
+            ) {
+                self.indexed_flow(result, None, w.to_f64(), bwd_value, fwd_value);
+            }
+
+            fn edge_flow(
+                &self,
+                result: &mut VecN<$ty, N>,
+                idx: usize,
+                w: W,
+                bwd_value: &VecN<$ty, N>,
+                fwd_value: &VecN<$ty, N>,
+            ) {
+                let w = w.to_f64();
+                self.indexed_flow(result, Some(idx), w, bwd_value, fwd_value);
+            }
+        }
+    };
+}
+
+impl_flow_law!(f32);
+impl_flow_law!(f64);
+
+mod msg {
+    pub const NO_MATRICES: &str = "Matrices of edges must not be empty.";
+    pub const NO_EDGE_IDX: &str = "Per-edge matrices require edge index.";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flow_matches_edge_flow_for_shared_matrix() {
+        let law = CrossDiffusion::new([[1.0, 0.5], [0.0, 2.0]]);
+        let bwd = VecN::new([1.0_f64, 2.0]);
+        let fwd = VecN::new([4.0, 0.0]);
+        let mut flow = VecN::default();
+        let mut edge_flow = VecN::default();
+        FlowLaw::<_, f64>::flow(&law, &mut flow, 2.0, &bwd, &fwd);
+        FlowLaw::<_, f64>::edge_flow(&law, &mut edge_flow, 3, 2.0, &bwd, &fwd);
+        assert_eq!(flow, VecN::new([4.0, -8.0]));
+        assert_eq!(flow, edge_flow);
+    }
+
+    #[test]
+    fn edge_flow_uses_matrix_of_edge() {
+        let law = CrossDiffusion::per_edge(vec![[[1.0]], [[3.0]]]);
+        let bwd = VecN::new([0.0_f64]);
+        let fwd = VecN::new([1.0]);
+        let mut result = VecN::default();
+        FlowLaw::<_, f64>::edge_flow(&law, &mut result, 1, 1.0, &bwd, &fwd);
+        assert_eq!(result, VecN::new([3.0]));
+    }
+}
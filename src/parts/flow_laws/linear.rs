@@ -0,0 +1,22 @@
+//! Provider of [`Linear`].
+
+use crate::ode::values::Value;
+use crate::parts::{FlowLaw, Weight};
+
+/// Linear flow law.
+///
+/// Flow is `w * d` (`d` is `fwd_value - bwd_value`).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Linear;
+
+impl<V, W> FlowLaw<V, W> for Linear
+where
+    V: Value,
+    W: Weight,
+{
+    fn flow(&self, result: &mut V, w: W, bwd_value: &V, fwd_value: &V) {
+        result.clone_from(fwd_value);
+        *result -= bwd_value;
+        w.scale(result);
+    }
+}
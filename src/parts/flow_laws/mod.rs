@@ -0,0 +1,97 @@
+//! Flow laws.
+//!
+//! Laws other than [`Linear`] are implemented for scalar values
+//! ([`f32`] and [`f64`]), except [`PerComponent`] and [`CrossDiffusion`]
+//! which are implemented for vector values
+//! ([`VecN`](crate::ode::values::VecN) of [`f32`] and [`f64`]), and
+//! [`QuantumWalk`] which is implemented for complex values
+//! ([`Complex`](crate::ode::values::Complex) of [`f32`] and [`f64`]).
+
+pub use bounded_confidence::*;
+pub use cross_diffusion::*;
+pub use linear::*;
+pub use p_laplacian::*;
+pub use per_component::*;
+pub use quantum_walk::*;
+pub use saturating::*;
+pub use square_root::*;
+
+mod bounded_confidence;
+mod cross_diffusion;
+mod linear;
+mod p_laplacian;
+mod per_component;
+mod quantum_walk;
+mod saturating;
+mod square_root;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ode::values::VArr;
+    use crate::parts::{FlowLaw, NdeqNet};
+    use crate::util::TestNet;
+    use std::rc::Rc;
+
+    fn scalar_laws() -> Vec<Rc<dyn FlowLaw<f64, f64>>> {
+        vec![
+            Rc::new(Linear),
+            Rc::new(PLaplacian::new(3.0)),
+            Rc::new(PLaplacian::new(1.5)),
+            Rc::new(Saturating::new(0.5)),
+            Rc::new(SquareRoot),
+            Rc::new(BoundedConfidence::new(2.0)),
+        ]
+    }
+
+    #[test]
+    fn scalar_laws_are_antisymmetric() {
+        let pairs = [(0.0, 1.0), (-2.0, 0.5), (3.0, 3.0), (1.0, -0.25)];
+        for law in scalar_laws() {
+            for (x, y) in pairs {
+                let mut fwd = 0.0;
+                let mut bwd = 0.0;
+                law.flow(&mut fwd, 1.5, &x, &y);
+                law.flow(&mut bwd, 1.5, &y, &x);
+                assert_eq!(fwd, -bwd);
+                assert!(fwd * (y - x) >= 0.0);
+            }
+        }
+    }
+
+    #[test]
+    fn scalar_laws_conserve_total_on_undirected_net() {
+        for law in scalar_laws() {
+            let values = vec![1.0, 4.0, 0.5, 2.0];
+            let edges = vec![(0, 1, 0.5), (1, 2, 2.0), (2, 0, 1.5), (3, 1, 0.25)];
+            let net = TestNet::new(values, edges);
+            let value = VArr::new(net.values());
+            let mut result = value.clone();
+            net.flow_slope(law)(&mut result, 0.0, &value);
+            assert!(result.as_ref().iter().sum::<f64>().abs() < 1e-12);
+            assert!(result.as_ref().iter().any(|x| *x != 0.0));
+        }
+    }
+
+    #[test]
+    fn bounded_confidence_ignores_far_values() {
+        let law = BoundedConfidence::new(1.0);
+        let mut result = 1.0;
+        FlowLaw::<f64, f64>::flow(&law, &mut result, 2.0, &0.0, &1.0);
+        assert_eq!(result, 0.0);
+        FlowLaw::<f64, f64>::flow(&law, &mut result, 2.0, &0.0, &0.5);
+        assert_eq!(result, 1.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Exponent must be greater than one and finite.")]
+    fn p_laplacian_rejects_small_p() {
+        PLaplacian::new(1.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Threshold must be non-negative and finite.")]
+    fn bounded_confidence_rejects_nan_threshold() {
+        BoundedConfidence::new(f64::NAN);
+    }
+}
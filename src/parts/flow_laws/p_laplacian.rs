@@ -0,0 +1,57 @@
+//! Provider of [`PLaplacian`].
+
+use crate::parts::{FlowLaw, Weight};
+
+/// Flow law of [p-Laplacian].
+///
+/// Flow is `w * |d|^(p - 2) * d` (`d` is `fwd_value - bwd_value`).
+///
+/// [p-Laplacian]: https://en.wikipedia.org/wiki/P-Laplacian
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PLaplacian {
+    /// Exponent `p`.
+    p: f64,
+}
+
+impl PLaplacian {
+    /// Creates a new instance.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `p` is not greater than one or NaN or infinity.
+    pub fn new(p: f64) -> Self {
+        assert!(p.is_finite() && p > 1.0, "{}", msg::INVALID_P);
+        Self { p }
+    }
+
+    /// Returns exponent `p`.
+    pub fn p(&self) -> f64 {
+        self.p
+    }
+}
+
+macro_rules! impl_flow_law {
+    ($ty:ty) => {
+        impl<W> FlowLaw<$ty, W> for PLaplacian
+        where
+            W: Weight,
+        {
+            fn flow(&self, result: &mut $ty, w: W, bwd_value: &$ty, fwd_value: &$ty) {
+                let w = w.to_f64() as $ty;
+                let d = fwd_value - bwd_value;
+                let p = self.p as $ty;
+                *result = match d == 0.0 {
+                    true => 0.0,
+                    false => w * d.abs().powf(p - 2.0) * d,
+                };
+            }
+        }
+    };
+}
+
+impl_flow_law!(f32);
+impl_flow_law!(f64);
+
+mod msg {
+    pub const INVALID_P: &str = "Exponent must be greater than one and finite.";
+}
@@ -0,0 +1,181 @@
+//! Provider of [`PerComponent`].
+
+use crate::ode::values::VecN;
+use crate::parts::{FlowLaw, Weight};
+
+/// Linear flow law with per-component rates.
+///
+/// Flow of component `k` is `w * r_k * d_k` (`d` is `fwd_value - bwd_value`
+/// and `r` is rates of edge). So, each species can have its own diffusivity,
+/// and it can vary by edge (like membranes with different permeabilities).
+#[derive(Clone, Debug, PartialEq)]
+pub struct PerComponent<const N: usize> {
+    /// Rates `r` of edges (single item is shared by all edges).
+    rates: Vec<[f64; N]>,
+}
+
+impl<const N: usize> PerComponent<N> {
+    /// Creates a new instance with rates shared by all edges.
+    pub fn new(rates: [f64; N]) -> Self {
+        Self { rates: vec![rates] }
+    }
+
+    /// Creates a new instance with rates of each edge.
+    ///
+    /// Item `i` of `rates` is rates of edge `i` in
+    /// [`NdeqNet::edges`](crate::parts::NdeqNet::edges) order. So,
+    /// [`flow`](FlowLaw::flow) (without edge index) panics, and network must
+    /// not have more edges than `rates`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rates` is empty.
+    pub fn per_edge(rates: Vec<[f64; N]>) -> Self {
+        assert!(!rates.is_empty(), "{}", msg::NO_RATES);
+        Self { rates }
+    }
+
+    /// Returns rates of edges (single item is shared by all edges).
+    pub fn rates(&self) -> &[[f64; N]] {
+        &self.rates
+    }
+
+    /// Returns rates of edge `idx` (`None` for unknown edge).
+    fn edge_rates(&self, idx: Option<usize>) -> &[f64; N] {
+        match self.rates.len() {
+            1 => &self.rates[0],
+            _ => &self.rates[idx.expect(msg::NO_EDGE_IDX)],
+        }
+    }
+}
+
+/// Flow calculation with optional edge index.
+///
+/// [`FlowLaw::flow`] and [`FlowLaw::edge_flow`] share this. So, they give
+/// same flow for shared coefficients.
+trait IndexedFlow<V> {
+    /// Calculates flow of edge `idx` (`None` for unknown edge).
+    fn indexed_flow(
+        &self,
+        result: &mut V,
+        idx: Option<usize>,
+        w: f64,
+        bwd_value: &V,
+        fwd_value: &V,
+    );
+}
+
+macro_rules! impl_flow_law {
+    ($ty:ty) => {
+        impl<const N: usize> IndexedFlow<VecN<$ty, N>> for PerComponent<N> {
+            fn indexed_flow(
+                &self,
+                result: &mut VecN<$ty, N>,
+                idx: Option<usize>,
+                w: f64,
+                bwd_value: &VecN<$ty, N>,
+                fwd_value: &VecN<$ty, N>,
+            ) {
+                let w = w as $ty;
+                let rates = self.edge_rates(idx);
+                for k in 0..N {
+                    let d = fwd_value[k] - bwd_value[k];
+                    result[k] = w * rates[k] as $ty * d;
+                }
+            }
+        }
+
+        impl<const N: usize, W> FlowLaw<VecN<$ty, N>, W> for PerComponent<N>
+        where
+            W: Weight,
+        {
+            fn flow(
+                &self,
+                result: &mut VecN<$ty, N>,
+                w: W,
+                bwd_value: &VecN<$ty, N>,
+                fwd_value: &VecN<$ty, N>,
+            ) {
+                self.indexed_flow(result, None, w.to_f64(), bwd_value, fwd_value);
+            }
+
+            fn edge_flow(
+                &self,
+                result: &mut VecN<$ty, N>,
+                idx: usize,
+                w: W,
+                bwd_value: &VecN<$ty, N>,
+                fwd_value: &VecN<$ty, N>,
+            ) {
+                let w = w.to_f64();
+                self.indexed_flow(result, Some(idx), w, bwd_value, fwd_value);
+            }
+        }
+    };
+}
+
+impl_flow_law!(f32);
+impl_flow_law!(f64);
+
+mod msg {
+    pub const NO_RATES: &str = "Rates of edges must not be empty.";
+    pub const NO_EDGE_IDX: &str = "Per-edge rates require edge index.";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ode::values::VArr;
+    use crate::parts::{CompiledNet, EdgeMode, NdeqNet};
+    use crate::util::TestNet;
+    use std::rc::Rc;
+
+    #[test]
+    fn per_edge_rates_follow_edge_order() {
+        for mode in [EdgeMode::Undirected, EdgeMode::OutFlow] {
+            let values = vec![
+                VecN::new([1.0_f64, 2.0]),
+                VecN::new([0.0, 0.0]),
+                VecN::new([4.0, 1.0]),
+            ];
+            let mut net = TestNet::new(values, vec![(1, 0, 1.0_f64), (1, 2, 2.0)]);
+            net.mode = mode;
+            let law = Rc::new(PerComponent::per_edge(vec![[1.0, 0.0], [0.0, 0.5]]));
+            let compiled = CompiledNet::new(&net);
+            let value = VArr::new(net.values());
+            let mut expected = value.clone();
+            let mut actual = value.clone();
+            net.flow_slope(law.clone())(&mut expected, 0.0, &value);
+            compiled.flow_slope(law)(&mut actual, 0.0, &value);
+
+            match mode {
+                EdgeMode::OutFlow => assert_eq!(expected[2], VecN::new([0.0, -1.0])),
+                _ => assert_eq!(expected[1], VecN::new([1.0, 1.0])),
+            }
+
+            assert_eq!(expected, actual);
+        }
+    }
+
+    #[test]
+    fn flow_matches_edge_flow_for_shared_rates() {
+        let law = PerComponent::new([1.0, 0.5]);
+        let bwd = VecN::new([1.0_f64, 2.0]);
+        let fwd = VecN::new([4.0, 0.0]);
+        let mut flow = VecN::default();
+        let mut edge_flow = VecN::default();
+        FlowLaw::<_, f64>::flow(&law, &mut flow, 2.0, &bwd, &fwd);
+        FlowLaw::<_, f64>::edge_flow(&law, &mut edge_flow, 3, 2.0, &bwd, &fwd);
+        assert_eq!(flow, VecN::new([6.0, -2.0]));
+        assert_eq!(flow, edge_flow);
+    }
+
+    #[test]
+    #[should_panic(expected = "Per-edge rates require edge index.")]
+    fn flow_of_per_edge_rates_panics() {
+        let law = PerComponent::per_edge(vec![[1.0], [2.0]]);
+        let value = VecN::new([1.0_f64]);
+        let mut result = VecN::default();
+        FlowLaw::<_, f64>::flow(&law, &mut result, 1.0, &value, &value);
+    }
+}
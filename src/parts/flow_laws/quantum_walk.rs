@@ -0,0 +1,47 @@
+//! Provider of [`QuantumWalk`].
+
+use crate::ode::values::Complex;
+use crate::parts::{FlowLaw, Weight};
+
+/// Flow law of [continuous-time quantum walk].
+///
+/// Flow is `i * w * d` (`d` is `fwd_value - bwd_value`). So, slope of node
+/// values `psi` becomes `-i * L * psi` (Schrodinger equation with Laplacian
+/// `L` as Hamiltonian).
+///
+/// Norm `sum_i(|psi_i|^2)` is conserved if `L` is Hermitian (undirected
+/// edges without capacities, with [`Laplacian::Combinatorial`] or
+/// [`Laplacian::SymmetricNormalized`]). Explicit solvers slowly break it, so
+/// use norm-preserving solver like
+/// [`CrankNicolson`](crate::ode::solver::solvers::CrankNicolson).
+///
+/// [continuous-time quantum walk]: https://en.wikipedia.org/wiki/Continuous-time_quantum_walk
+/// [`Laplacian::Combinatorial`]: crate::parts::Laplacian::Combinatorial
+/// [`Laplacian::SymmetricNormalized`]: crate::parts::Laplacian::SymmetricNormalized
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct QuantumWalk;
+
+macro_rules! impl_flow_law {
+    ($ty:ty) => {
+        impl<W> FlowLaw<Complex<$ty>, W> for QuantumWalk
+        where
+            W: Weight,
+        {
+            fn flow(
+                &self,
+                result: &mut Complex<$ty>,
+                w: W,
+                bwd_value: &Complex<$ty>,
+                fwd_value: &Complex<$ty>,
+            ) {
+                result.clone_from(fwd_value);
+                *result -= bwd_value;
+                w.scale(result);
+                *result = result.mul_i();
+            }
+        }
+    };
+}
+
+impl_flow_law!(f32);
+impl_flow_law!(f64);
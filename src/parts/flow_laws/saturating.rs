@@ -0,0 +1,53 @@
+//! Provider of [`Saturating`].
+
+use crate::parts::{FlowLaw, Weight};
+
+/// Saturating flow law.
+///
+/// Flow is `w * s * tanh(d / s)` (`d` is `fwd_value - bwd_value` and `s` is
+/// scale). So, flow is almost linear for small `d`, and bounded by `w * s`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Saturating {
+    /// Scale `s`.
+    scale: f64,
+}
+
+impl Saturating {
+    /// Creates a new instance.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `scale` is zero or negative or NaN or infinity.
+    pub fn new(scale: f64) -> Self {
+        assert!(scale.is_finite() && scale > 0.0, "{}", msg::INVALID_SCALE);
+        Self { scale }
+    }
+
+    /// Returns scale `s`.
+    pub fn scale(&self) -> f64 {
+        self.scale
+    }
+}
+
+macro_rules! impl_flow_law {
+    ($ty:ty) => {
+        impl<W> FlowLaw<$ty, W> for Saturating
+        where
+            W: Weight,
+        {
+            fn flow(&self, result: &mut $ty, w: W, bwd_value: &$ty, fwd_value: &$ty) {
+                let w = w.to_f64() as $ty;
+                let d = fwd_value - bwd_value;
+                let s = self.scale as $ty;
+                *result = w * s * (d / s).tanh();
+            }
+        }
+    };
+}
+
+impl_flow_law!(f32);
+impl_flow_law!(f64);
+
+mod msg {
+    pub const INVALID_SCALE: &str = "Scale must be positive and finite.";
+}
@@ -0,0 +1,27 @@
+//! Provider of [`SquareRoot`].
+
+use crate::parts::{FlowLaw, Weight};
+
+/// Square root flow law (like turbulent pipe flow).
+///
+/// Flow is `w * sign(d) * sqrt(|d|)` (`d` is `fwd_value - bwd_value`).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SquareRoot;
+
+macro_rules! impl_flow_law {
+    ($ty:ty) => {
+        impl<W> FlowLaw<$ty, W> for SquareRoot
+        where
+            W: Weight,
+        {
+            fn flow(&self, result: &mut $ty, w: W, bwd_value: &$ty, fwd_value: &$ty) {
+                let w = w.to_f64() as $ty;
+                let d = fwd_value - bwd_value;
+                *result = w * d.abs().sqrt().copysign(d);
+            }
+        }
+    };
+}
+
+impl_flow_law!(f32);
+impl_flow_law!(f64);
@@ -0,0 +1,53 @@
+//! Provider of [`FluxLimiter`].
+
+/// Flux limiter of advection.
+///
+/// This trait calculates advective flux of edge from upwind node value
+/// (donor value). Default flux limiter of simulator is
+/// [`Upwind`](crate::parts::flux_limiters::Upwind).
+///
+/// Upstream value is value of upwind side of donor. Simulator gives mean of
+/// donors of advection edges into donor node (weighted by their velocities),
+/// or donor value itself if there is no such edge. Receiver value is value
+/// of downwind node of edge. High resolution limiters (like
+/// [`VanLeer`](crate::parts::flux_limiters::VanLeer)) use them to detect
+/// smoothness of values.
+///
+/// # Closures
+///
+/// Closures with same signature as [`flux`](Self::flux) are also flux
+/// limiters.
+pub trait FluxLimiter<V> {
+    /// Calculates flux from donor node with non-negative velocity, and
+    /// writes it to `result`.
+    fn flux(
+        &self,
+        result: &mut V,
+        velocity: f64,
+        upstream_value: &V,
+        donor_value: &V,
+        receiver_value: &V,
+    );
+}
+
+impl<V, F> FluxLimiter<V> for F
+where
+    F: Fn(&mut V, f64, &V, &V, &V),
+{
+    fn flux(
+        &self,
+        result: &mut V,
+        velocity: f64,
+        upstream_value: &V,
+        donor_value: &V,
+        receiver_value: &V,
+    ) {
+        self(
+            result,
+            velocity,
+            upstream_value,
+            donor_value,
+            receiver_value,
+        )
+    }
+}
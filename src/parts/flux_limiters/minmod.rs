@@ -0,0 +1,44 @@
+//! Provider of [`Minmod`].
+
+use crate::parts::FluxLimiter;
+
+/// High resolution flux with [minmod] limiter.
+///
+/// Flux is `velocity * (x_d + phi(r) * (x_r - x_d) / 2)` (`x_u`, `x_d` and
+/// `x_r` are upstream, donor and receiver values, `r` is
+/// `(x_d - x_u) / (x_r - x_d)` and `phi(r)` is `max(0, min(1, r))`). So,
+/// flux is second order accurate in smooth region, and it falls back to
+/// [`Upwind`](super::Upwind) flux around extrema. On chain of nodes, this is
+/// total variation diminishing, and it keeps node values non-negative if
+/// step size is half of upwind condition. This is implemented for scalar
+/// values ([`f32`] and [`f64`]).
+///
+/// [minmod]: https://en.wikipedia.org/wiki/Flux_limiter
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Minmod;
+
+macro_rules! impl_flux_limiter {
+    ($ty:ty) => {
+        impl FluxLimiter<$ty> for Minmod {
+            fn flux(
+                &self,
+                result: &mut $ty,
+                velocity: f64,
+                upstream_value: &$ty,
+                donor_value: &$ty,
+                receiver_value: &$ty,
+            ) {
+                let d = receiver_value - donor_value;
+                let phi = match d == 0.0 {
+                    true => 0.0,
+                    false => ((donor_value - upstream_value) / d).clamp(0.0, 1.0),
+                };
+
+                *result = velocity as $ty * (donor_value + phi * d / 2.0);
+            }
+        }
+    };
+}
+
+impl_flux_limiter!(f32);
+impl_flux_limiter!(f64);
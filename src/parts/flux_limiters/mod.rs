@@ -0,0 +1,11 @@
+//! Flux limiters.
+
+pub use minmod::*;
+pub use positive_part::*;
+pub use upwind::*;
+pub use van_leer::*;
+
+mod minmod;
+mod positive_part;
+mod upwind;
+mod van_leer;
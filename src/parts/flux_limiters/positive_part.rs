@@ -0,0 +1,29 @@
+//! Provider of [`PositivePart`].
+
+use crate::parts::FluxLimiter;
+
+/// Upwind flux of positive part of donor value.
+///
+/// Flux is `velocity * max(donor_value, 0)`. So, advection never carries
+/// value out of empty (or negative) node, and it never carries negative
+/// value to receiver. Note that this only clips flux. Node values can still
+/// become negative if step size is too large for [`Upwind`](super::Upwind)
+/// condition. Use Patankar-type solver (like
+/// [`NetPatankar`](crate::net_ode::solver::adapters::NetPatankar)) to keep
+/// node values non-negative with any step size. This is implemented for
+/// scalar values ([`f32`] and [`f64`]).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PositivePart;
+
+macro_rules! impl_flux_limiter {
+    ($ty:ty) => {
+        impl FluxLimiter<$ty> for PositivePart {
+            fn flux(&self, result: &mut $ty, velocity: f64, _: &$ty, donor_value: &$ty, _: &$ty) {
+                *result = velocity as $ty * donor_value.max(0.0);
+            }
+        }
+    };
+}
+
+impl_flux_limiter!(f32);
+impl_flux_limiter!(f64);
@@ -0,0 +1,23 @@
+//! Provider of [`Upwind`].
+
+use crate::ode::values::{RF64, Value};
+use crate::parts::FluxLimiter;
+
+/// Plain upwind flux (no limiting).
+///
+/// Flux is `velocity * donor_value`. This is first order accurate, and it
+/// keeps node values non-negative if step size `h` satisfies
+/// `h * sum(velocity) <= capacity` for outgoing advection edges of each node
+/// (with explicit Euler method).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Upwind;
+
+impl<V> FluxLimiter<V> for Upwind
+where
+    V: Value,
+{
+    fn flux(&self, result: &mut V, velocity: f64, _: &V, donor_value: &V, _: &V) {
+        result.clone_from(donor_value);
+        *result *= RF64(velocity);
+    }
+}
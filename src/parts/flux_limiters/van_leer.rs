@@ -0,0 +1,46 @@
+//! Provider of [`VanLeer`].
+
+use crate::parts::FluxLimiter;
+
+/// High resolution flux with [van Leer] limiter.
+///
+/// Flux is `velocity * (x_d + phi(r) * (x_r - x_d) / 2)` (`x_u`, `x_d` and
+/// `x_r` are upstream, donor and receiver values, `r` is
+/// `(x_d - x_u) / (x_r - x_d)` and `phi(r)` is `(r + |r|) / (1 + |r|)`).
+/// This is smoother than [`Minmod`](super::Minmod), and has same properties
+/// (second order in smooth region, total variation diminishing on chain of
+/// nodes, non-negative if step size is half of [`Upwind`](super::Upwind)
+/// condition). This is implemented for scalar values ([`f32`] and [`f64`]).
+///
+/// [van Leer]: https://en.wikipedia.org/wiki/Flux_limiter
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct VanLeer;
+
+macro_rules! impl_flux_limiter {
+    ($ty:ty) => {
+        impl FluxLimiter<$ty> for VanLeer {
+            fn flux(
+                &self,
+                result: &mut $ty,
+                velocity: f64,
+                upstream_value: &$ty,
+                donor_value: &$ty,
+                receiver_value: &$ty,
+            ) {
+                let d = receiver_value - donor_value;
+                let phi = match d == 0.0 {
+                    true => 0.0,
+                    false => {
+                        let r = (donor_value - upstream_value) / d;
+                        (r + r.abs()) / (1.0 + r.abs())
+                    }
+                };
+
+                *result = velocity as $ty * (donor_value + phi * d / 2.0);
+            }
+        }
+    };
+}
+
+impl_flux_limiter!(f32);
+impl_flux_limiter!(f64);
@@ -0,0 +1,100 @@
+//! Provider of [`Laplacian`].
+
+use crate::ode::values::{RF64, VArr, Value};
+
+/// Laplacian variant of network diffusion.
+///
+/// Degree `d_i` of node is sum of weights of edges which move its value (see
+/// [`EdgeMode`](crate::parts::EdgeMode)). Nodes with zero degree are treated
+/// as degree `1.0`.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Laplacian {
+    /// Combinatorial Laplacian `L = D - W`.
+    ///
+    /// Slope of node is `sum_j(w_ij * (x_j - x_i))`.
+    #[default]
+    Combinatorial,
+
+    /// Random-walk Laplacian `D^-1 * L`.
+    ///
+    /// Slope of node is divided by its degree. So, hubs move as fast as
+    /// other nodes.
+    RandomWalk,
+
+    /// Symmetric normalized Laplacian `D^-1/2 * L * D^-1/2`.
+    ///
+    /// Slope of node is `sum_j(w_ij * x_j / sqrt(d_i * d_j)) - x_i`.
+    SymmetricNormalized,
+}
+
+impl Laplacian {
+    /// Returns values to diffuse (values are scaled into `scaled` if needed).
+    pub(crate) fn scale_values<'v, V: Value>(
+        self,
+        value: &'v VArr<V>,
+        degrees: &[f64],
+        scaled: &'v mut VArr<V>,
+    ) -> &'v VArr<V> {
+        if self != Self::SymmetricNormalized {
+            return value;
+        }
+
+        scaled.clone_from(value);
+        for (idx, &d) in degrees.iter().enumerate() {
+            if d != 0.0 {
+                scaled[idx] /= RF64(d.sqrt());
+            }
+        }
+
+        scaled
+    }
+
+    /// Scales slope of nodes by their degrees.
+    pub(crate) fn scale_result<V: Value>(self, result: &mut VArr<V>, degrees: &[f64]) {
+        let scale = match self {
+            Self::Combinatorial => return,
+            Self::RandomWalk => |d: f64| d,
+            Self::SymmetricNormalized => |d: f64| d.sqrt(),
+        };
+
+        for (idx, &d) in degrees.iter().enumerate() {
+            if d != 0.0 {
+                result[idx] /= RF64(scale(d));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parts::NdeqNet;
+    use crate::util::TestNet;
+
+    #[test]
+    fn slope_of_variants() {
+        let cases = [
+            (Laplacian::Combinatorial, [-1.0, 1.0, 0.0]),
+            (Laplacian::RandomWalk, [-1.0, 0.25, 0.0]),
+            (Laplacian::SymmetricNormalized, [-1.0, 0.5, 0.0]),
+        ];
+
+        for (laplacian, expected) in cases {
+            let mut net = TestNet::new(vec![1.0_f64, 0.0, 0.0], vec![(0, 1, 1.0_f64), (1, 2, 3.0)]);
+            net.laplacian = laplacian;
+            let value = VArr::new(net.values());
+            let mut result = value.clone();
+            net.slope()(&mut result, 0.0, &value);
+
+            let mut product = [0.0; 3];
+            for (row, col, entry) in net.laplacian_entries(0.0) {
+                product[row] -= entry * value[col];
+            }
+
+            for idx in 0..3 {
+                assert!((result[idx] - expected[idx]).abs() < 1e-12);
+                assert!((product[idx] - expected[idx]).abs() < 1e-12);
+            }
+        }
+    }
+}
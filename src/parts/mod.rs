@@ -1,7 +1,24 @@
 //! Network parts.
 
+pub mod flow_laws;
+pub mod flux_limiters;
+
+pub use compiled_net::*;
+pub use edge_mode::*;
+pub use flow_law::*;
+pub use flux_limiter::*;
+pub use laplacian::*;
 pub use ndeq_net::*;
 pub use ndeq_node::*;
+pub use schedule::*;
+pub use weight::*;
 
+mod compiled_net;
+mod edge_mode;
+mod flow_law;
+mod flux_limiter;
+mod laplacian;
 mod ndeq_net;
 mod ndeq_node;
+mod schedule;
+mod weight;
@@ -1,20 +1,122 @@
 //! Provider of [`NdeqNet`].
 
 use crate::ode::Slope;
-use crate::ode::values::{RF32, VArr, Value};
+use crate::ode::values::{RF32, RF64, VArr, Value};
+use crate::parts::{EdgeMode, FlowLaw, Laplacian, Weight};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::iter;
 use std::rc::Rc;
 
 /// Abstraction trait for Network.
-pub trait NdeqNet<V>
+///
+/// Type parameter `W` is edge weight type (see [`Weight`]).
+pub trait NdeqNet<V, W = f32>
 where
     V: Value,
+    W: Weight,
 {
     /// Returns edges.
     ///
     /// # Panics
     ///
     /// Panics if `self` or its nodes are currently mutably borrowed.
-    fn edges(&self) -> Box<dyn Iterator<Item = (usize, usize, f32)> + '_>;
+    fn edges(&self) -> Box<dyn Iterator<Item = (usize, usize, W)> + '_>;
+
+    /// Returns edges at time `t`.
+    ///
+    /// This is for time-varying edge weights. Returned edges must be same as
+    /// [`edges`](Self::edges) except their weights. Default implementation
+    /// returns [`edges`](Self::edges). If this is overridden,
+    /// [`is_time_varying`](Self::is_time_varying) should be overridden too.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` or its nodes are currently mutably borrowed.
+    fn edges_at(&self, t: f64) -> Box<dyn Iterator<Item = (usize, usize, W)> + '_> {
+        let _ = t;
+        self.edges()
+    }
+
+    /// Returns `true` if edge weights are varied by time.
+    ///
+    /// Default implementation returns `false`.
+    fn is_time_varying(&self) -> bool {
+        false
+    }
+
+    /// Returns indices of fixed nodes.
+    ///
+    /// Fixed node is excluded from integration, but its neighbors still
+    /// exchange flow with it. Default implementation returns nothing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` or its nodes are currently mutably borrowed.
+    fn fixed_nodes(&self) -> Box<dyn Iterator<Item = usize> + '_> {
+        Box::new(iter::empty())
+    }
+
+    /// Returns capacities of nodes.
+    ///
+    /// Capacity (like heat capacity or tank area) divides diffusion flow of
+    /// node. So, `sum_i(c_i * x_i)` is conserved instead of `sum_i(x_i)`.
+    /// Sources are not divided by capacity. Default implementation returns
+    /// nothing, and capacities of nodes not returned are `1.0`.
+    ///
+    /// Capacity must be positive and finite, and each node must be returned
+    /// at most once. [`NdeqSim`](crate::net_ode::NdeqSim) and
+    /// [`CompiledNet`](crate::parts::CompiledNet) panic otherwise.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` or its nodes are currently mutably borrowed.
+    fn capacities(&self) -> Box<dyn Iterator<Item = (usize, f64)> + '_> {
+        Box::new(iter::empty())
+    }
+
+    /// Returns constant sources of nodes.
+    ///
+    /// Source is rate added to slope of node (negative rate works as sink).
+    /// Default implementation returns nothing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` or its nodes are currently mutably borrowed.
+    fn sources(&self) -> Box<dyn Iterator<Item = (usize, V)> + '_> {
+        Box::new(iter::empty())
+    }
+
+    /// Returns advection edges.
+    ///
+    /// Each item is `(from_idx, to_idx, velocity)`. Advection carries value
+    /// from `from_idx` node to `to_idx` node by upwind flux (or reverse
+    /// direction if velocity is negative). Like diffusion flow, advective
+    /// flux is divided by capacities of nodes. Default implementation
+    /// returns nothing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` or its nodes are currently mutably borrowed.
+    fn advections(&self) -> Box<dyn Iterator<Item = (usize, usize, f64)> + '_> {
+        Box::new(iter::empty())
+    }
+
+    /// Returns edge mode.
+    ///
+    /// Default implementation returns [`EdgeMode::InFlow`].
+    fn edge_mode(&self) -> EdgeMode {
+        EdgeMode::InFlow
+    }
+
+    /// Returns Laplacian variant of diffusion.
+    ///
+    /// Default implementation returns [`Laplacian::Combinatorial`].
+    fn laplacian(&self) -> Laplacian {
+        Laplacian::Combinatorial
+    }
 
     /// Imports node values from slice.
     ///
@@ -33,24 +135,404 @@ where
     /// Panics if `self` or its nodes are currently borrowed.
     fn export_values(&self, values: &mut Vec<V>);
 
+    /// Exports stable node ids to vector.
+    ///
+    /// Node ids are keys that are not changed by topology changes, and they
+    /// are written in the same order as [`export_values`](Self::export_values).
+    /// Default implementation exports nothing. In this case, node indices are
+    /// treated as node ids. Node values are not remapped by ids, because
+    /// they are owned by `self`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` or its nodes are currently mutably borrowed.
+    fn export_ids(&self, ids: &mut Vec<usize>) {
+        ids.clear();
+    }
+
+    /// Returns nodes count.
+    ///
+    /// Default implementation counts exported node values. Implementors can
+    /// override this with cheaper way.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` or its nodes are currently mutably borrowed.
+    fn nodes_count(&self) -> usize {
+        let mut values = Vec::new();
+        self.export_values(&mut values);
+        values.len()
+    }
+
+    /// Returns hash of topology.
+    ///
+    /// Returned value must be changed when edges or capacities are changed.
+    /// Default implementation hashes all edges (including weights) and
+    /// capacities. Implementors can override this with cheaper way like a
+    /// revision counter.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` or its nodes are currently mutably borrowed.
+    fn topology_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        for (bwd_idx, fwd_idx, w) in self.edges() {
+            (bwd_idx, fwd_idx, w.to_f64().to_bits()).hash(&mut hasher);
+        }
+
+        for (idx, c) in self.capacities() {
+            (idx, c.to_bits()).hash(&mut hasher);
+        }
+
+        hasher.finish()
+    }
+
+    /// Rebuilds cached structures.
+    ///
+    /// This is called by simulator when topology is changed.
+    /// Default implementation does nothing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` or its nodes are currently borrowed.
+    fn refresh(&self) {
+        // nop.
+    }
+
+    /// Returns target network if this wraps other network.
+    ///
+    /// Wrapper network (like [`CompiledNet`](crate::parts::CompiledNet)) may
+    /// reorder or duplicate edges of target network, or change edge mode.
+    /// Then, edges specified by users (like sensitivity edges of
+    /// [`NdeqSim`](crate::net_ode::NdeqSim)) are resolved against target
+    /// network. Default implementation returns `None`.
+    fn target(&self) -> Option<&dyn NdeqNet<V, W>> {
+        None
+    }
+
     /// Returns derivative function for network diffusion.
     ///
+    /// Time of returned function is [`f64`] regardless of simulation time type.
+    /// Diffusion operator is decided by [`laplacian`](Self::laplacian).
+    ///
     /// # Panics
     ///
     /// Panics if `self` or its nodes are currently mutably borrowed.
-    fn slope(&self) -> Rc<Slope<VArr<V>>> {
-        Rc::new(|result, value| {
+    fn slope(&self) -> Rc<Slope<'_, f64, VArr<V>>> {
+        let mode = self.edge_mode();
+        let laplacian = self.laplacian();
+        let degrees = RefCell::new(Degrees::default());
+        let scaled = RefCell::new(VArr::default());
+        Rc::new(move |result, t, value| {
+            let mut degrees = degrees.borrow_mut();
+            let mut scaled = scaled.borrow_mut();
+            degrees.sync(self, laplacian, t, value.len());
+            let value = laplacian.scale_values(value, &degrees.values, &mut scaled);
             result.fill_zero();
 
-            for (bwd_idx, fwd_idx, w) in self.edges() {
+            for (bwd_idx, fwd_idx, w) in self.edges_at(t) {
                 let bwd_value = &value[bwd_idx];
                 let fwd_value = &value[fwd_idx];
                 let mut flow = V::default();
                 flow += fwd_value;
                 flow -= bwd_value;
-                flow *= RF32(w);
-                result[bwd_idx] += &flow;
+                w.scale(&mut flow);
+                add_flow(result, mode, bwd_idx, fwd_idx, &flow);
+            }
+
+            laplacian.scale_result(result, &degrees.values);
+            for (idx, c) in self.capacities() {
+                result[idx] /= RF64(c);
+            }
+        })
+    }
+
+    /// Returns derivative function for network diffusion with flow law.
+    ///
+    /// Time of returned function is [`f64`] regardless of simulation time type.
+    /// Diffusion operator is decided by [`laplacian`](Self::laplacian).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` or its nodes are currently mutably borrowed.
+    fn flow_slope<'s>(&'s self, law: Rc<dyn FlowLaw<V, W> + 's>) -> Rc<Slope<'s, f64, VArr<V>>> {
+        let mode = self.edge_mode();
+        let laplacian = self.laplacian();
+        let degrees = RefCell::new(Degrees::default());
+        let scaled = RefCell::new(VArr::default());
+        let work = RefCell::new(V::default());
+        Rc::new(move |result, t, value| {
+            let mut degrees = degrees.borrow_mut();
+            let mut scaled = scaled.borrow_mut();
+            let mut flow = work.borrow_mut();
+            degrees.sync(self, laplacian, t, value.len());
+            let value = laplacian.scale_values(value, &degrees.values, &mut scaled);
+            result.fill_zero();
+
+            for (idx, (bwd_idx, fwd_idx, w)) in self.edges_at(t).enumerate() {
+                law.edge_flow(&mut flow, idx, w, &value[bwd_idx], &value[fwd_idx]);
+                add_flow(result, mode, bwd_idx, fwd_idx, &flow);
+            }
+
+            laplacian.scale_result(result, &degrees.values);
+            for (idx, c) in self.capacities() {
+                result[idx] /= RF64(c);
             }
         })
     }
+
+    /// Returns entries of Laplacian matrix at time `t`.
+    ///
+    /// Each entry is `(row, col, value)`, and duplicated entries are summed.
+    /// Matrix `L` includes Laplacian variant (see [`laplacian`](Self::laplacian))
+    /// and capacities. So, slope of linear diffusion is `-L * x`.
+    /// Entries are calculated in [`f64`], and converted to `W` at last. So,
+    /// [`f64`] weights are not truncated.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` or its nodes are currently mutably borrowed.
+    fn laplacian_entries(&self, t: f64) -> Vec<(usize, usize, W)> {
+        let mode = self.edge_mode();
+        let mut ret = Vec::new();
+        let mut degrees = Vec::<f64>::new();
+        let mut add = |bwd_idx: usize, fwd_idx: usize, w: W| {
+            let w = w.to_f64();
+            let len = bwd_idx.max(fwd_idx) + 1;
+            degrees.resize(degrees.len().max(len), 0.0);
+            degrees[bwd_idx] += w;
+            ret.push((bwd_idx, bwd_idx, w));
+            ret.push((bwd_idx, fwd_idx, -w));
+        };
+
+        for (bwd_idx, fwd_idx, w) in self.edges_at(t) {
+            if mode != EdgeMode::OutFlow {
+                add(bwd_idx, fwd_idx, w);
+            }
+
+            if mode != EdgeMode::InFlow {
+                add(fwd_idx, bwd_idx, w);
+            }
+        }
+
+        let degree = |idx: usize| match degrees.get(idx) {
+            Some(&d) if d != 0.0 => d,
+            _ => 1.0,
+        };
+
+        let laplacian = self.laplacian();
+        let capacities = self.capacities().collect::<HashMap<_, _>>();
+        for (row, col, value) in &mut ret {
+            *value /= match laplacian {
+                Laplacian::Combinatorial => 1.0,
+                Laplacian::RandomWalk => degree(*row),
+                Laplacian::SymmetricNormalized => (degree(*row) * degree(*col)).sqrt(),
+            };
+
+            if let Some(&c) = capacities.get(row) {
+                *value /= c;
+            }
+        }
+
+        let ret = ret.into_iter();
+        ret.map(|(row, col, value)| (row, col, W::from_f64(value)))
+            .collect()
+    }
+
+    /// Writes production-destruction terms of network diffusion to `result`.
+    ///
+    /// Terms are appended to `result` in the form of [`PdSlope`](crate::ode::PdSlope). Flow law is
+    /// linear law if `law` is `None`. Then, gain from the other node of edge
+    /// is weighted by the other node, and loss is weighted by the node itself.
+    /// (So, modified Patankar-Euler method becomes implicit Euler method.)
+    /// Net flow of other flow law is weighted by its donor node. Sum of terms
+    /// of each node is same as
+    /// [`slope`](Self::slope) (or [`flow_slope`](Self::flow_slope)) for all
+    /// Laplacian variants.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` or its nodes are currently mutably borrowed.
+    fn pd_terms(
+        &self,
+        result: &mut Vec<(usize, usize, V)>,
+        t: f64,
+        value: &VArr<V>,
+        law: Option<&dyn FlowLaw<V, W>>,
+    ) {
+        let laplacian = self.laplacian();
+        let mode = self.edge_mode();
+        let edges = self.edges_at(t).collect::<Vec<_>>();
+        let mut degrees = vec![0.0; value.len()];
+        for &(bwd_idx, fwd_idx, w) in &edges {
+            if mode != EdgeMode::InFlow {
+                degrees[fwd_idx] += w.to_f64();
+            }
+
+            if mode != EdgeMode::OutFlow {
+                degrees[bwd_idx] += w.to_f64();
+            }
+        }
+
+        let mut scaled = VArr::default();
+        let value = laplacian.scale_values(value, &degrees, &mut scaled);
+        let start = result.len();
+        for (idx, (bwd_idx, fwd_idx, w)) in edges.into_iter().enumerate() {
+            let Some(law) = law else {
+                let mut add = |node_idx: usize, other_idx: usize| {
+                    let mut gain = value[other_idx].clone();
+                    let mut loss = value[node_idx].clone();
+                    w.scale(&mut gain);
+                    w.scale(&mut loss);
+                    loss *= RF32(-1.0);
+                    result.push((node_idx, other_idx, gain));
+                    result.push((node_idx, node_idx, loss));
+                };
+
+                if mode != EdgeMode::InFlow {
+                    add(fwd_idx, bwd_idx);
+                }
+
+                if mode != EdgeMode::OutFlow {
+                    add(bwd_idx, fwd_idx);
+                }
+
+                continue;
+            };
+
+            let mut flow = V::default();
+            law.edge_flow(&mut flow, idx, w, &value[bwd_idx], &value[fwd_idx]);
+            if mode != EdgeMode::InFlow {
+                let mut flow = flow.clone();
+                flow *= RF32(-1.0);
+                result.push((fwd_idx, bwd_idx, flow));
+            }
+
+            if mode != EdgeMode::OutFlow {
+                result.push((bwd_idx, fwd_idx, flow));
+            }
+        }
+
+        let mut scales = vec![1.0; value.len()];
+        for (idx, c) in self.capacities() {
+            scales[idx] = c;
+        }
+
+        for (scale, d) in scales.iter_mut().zip(degrees) {
+            *scale *= match (laplacian, d != 0.0) {
+                (_, false) | (Laplacian::Combinatorial, _) => 1.0,
+                (Laplacian::RandomWalk, true) => d,
+                (Laplacian::SymmetricNormalized, true) => d.sqrt(),
+            };
+        }
+
+        for (idx, _, r) in &mut result[start..] {
+            if scales[*idx] != 1.0 {
+                *r /= RF64(scales[*idx]);
+            }
+        }
+    }
+}
+
+/// Adds edge flow to slope of nodes.
+fn add_flow<V: Value>(
+    result: &mut VArr<V>,
+    mode: EdgeMode,
+    bwd_idx: usize,
+    fwd_idx: usize,
+    flow: &V,
+) {
+    if mode != EdgeMode::OutFlow {
+        result[bwd_idx] += flow;
+    }
+
+    if mode != EdgeMode::InFlow {
+        result[fwd_idx] -= flow;
+    }
+}
+
+/// Cached node degrees.
+#[derive(Default)]
+struct Degrees {
+    /// Degrees of nodes (empty for combinatorial Laplacian).
+    values: Vec<f64>,
+
+    /// Time of degrees (`None` if not calculated yet).
+    time: Option<f64>,
+}
+
+impl Degrees {
+    /// Recalculates degrees if they are not cached for time `t`.
+    fn sync<N, V, W>(&mut self, net: &N, laplacian: Laplacian, t: f64, len: usize)
+    where
+        N: NdeqNet<V, W> + ?Sized,
+        V: Value,
+        W: Weight,
+    {
+        let cached = self.time.is_some() && !net.is_time_varying();
+        if laplacian == Laplacian::Combinatorial || cached || self.time == Some(t) {
+            return;
+        }
+
+        let mode = net.edge_mode();
+        self.values.clear();
+        self.values.resize(len, 0.0);
+        for (bwd_idx, fwd_idx, w) in net.edges_at(t) {
+            if mode != EdgeMode::OutFlow {
+                self.values[bwd_idx] += w.to_f64();
+            }
+
+            if mode != EdgeMode::InFlow {
+                self.values[fwd_idx] += w.to_f64();
+            }
+        }
+
+        self.time = Some(t);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parts::CompiledNet;
+    use crate::util::TestNet;
+
+    #[test]
+    fn pd_terms_sum_to_slope() {
+        for net in TestNet::variants() {
+            let value = VArr::new(net.values());
+            let mut expected = value.clone();
+            let mut terms = Vec::new();
+            net.slope()(&mut expected, 0.0, &value);
+            net.pd_terms(&mut terms, 0.0, &value, None);
+
+            let mut actual = [0.0; 4];
+            for (idx, _, r) in terms {
+                actual[idx] += r;
+            }
+
+            for idx in 0..4 {
+                assert!((expected[idx] - actual[idx]).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn f64_weights_are_not_truncated() {
+        let w = 1.0 + 1e-12;
+        assert_ne!(w as f32 as f64, w);
+
+        let net = TestNet::new(vec![1.0, 0.0], vec![(0, 1, w)]);
+        let compiled = CompiledNet::new(&net);
+        let value = VArr::new(net.values());
+        for target in [&net as &dyn NdeqNet<f64, f64>, &compiled] {
+            let mut result = value.clone();
+            target.slope()(&mut result, 0.0, &value);
+            assert_eq!(result, VArr::new(vec![-w, w]));
+        }
+
+        let mut entries = net.laplacian_entries(0.0);
+        entries.sort_by_key(|&(row, col, _)| (row, col));
+        assert_eq!(entries, [(0, 0, w), (0, 1, -w), (1, 0, -w), (1, 1, w)]);
+    }
 }
@@ -1,7 +1,14 @@
 //! Provider of [`NdeqNode`].
 
+use crate::parts::Weight;
+
 /// Abstraction trait for network node.
-pub trait NdeqNode<V> {
+///
+/// Type parameter `W` is edge weight type (see [`Weight`]).
+pub trait NdeqNode<V, W = f32>
+where
+    W: Weight,
+{
     /// Returns node index that is unique in network.
     ///
     /// # Panics
@@ -34,6 +41,6 @@ pub trait NdeqNode<V> {
     ///
     /// # Panics
     ///
-    /// Panics if target is currently mutably borrowed.    
-    fn edges(&self) -> Box<dyn Iterator<Item = (usize, f32)> + '_>;
+    /// Panics if target is currently mutably borrowed.
+    fn edges(&self) -> Box<dyn Iterator<Item = (usize, W)> + '_>;
 }
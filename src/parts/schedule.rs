@@ -0,0 +1,185 @@
+//! Provider of [`Schedule`].
+
+/// Piecewise-linear schedule.
+///
+/// This type is helper for time-varying edge weights (see
+/// [`NdeqNet::edges_at`](crate::parts::NdeqNet::edges_at)). Value between
+/// points is linearly interpolated, and value out of points is clamped to
+/// first or last point value. (Periodic schedule interpolates it between last
+/// point and first point of next period instead.)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Schedule {
+    /// Points of time and value.
+    points: Vec<(f64, f64)>,
+
+    /// Period of schedule.
+    period: Option<f64>,
+}
+
+impl Schedule {
+    /// Creates a new instance.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `points` is empty or its times are not sorted.
+    pub fn new(points: Vec<(f64, f64)>) -> Self {
+        assert!(!points.is_empty(), "{}", msg::EMPTY_POINTS);
+        assert!(points.is_sorted_by(|x, y| x.0 <= y.0), "{}", msg::UNSORTED);
+        Self {
+            points,
+            period: None,
+        }
+    }
+
+    /// Creates a new periodic instance (like daily schedule).
+    ///
+    /// Time is wrapped into range from zero to `period`. Value after last
+    /// point is interpolated toward first point at `first_time + period`. So,
+    /// schedule is continuous at period boundaries.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the following occurs.
+    ///
+    /// * `points` is empty or its times are not sorted.
+    /// * `period` is zero or negative or NaN or infinity.
+    /// * Times of `points` are out of range from zero to `period`
+    ///   (`period` itself is excluded).
+    pub fn periodic(points: Vec<(f64, f64)>, period: f64) -> Self {
+        assert!(
+            period.is_finite() && period > 0.0,
+            "{}",
+            msg::INVALID_PERIOD
+        );
+        assert!(
+            points.iter().all(|x| 0.0 <= x.0 && x.0 < period),
+            "{}",
+            msg::OUT_OF_PERIOD
+        );
+        Self {
+            period: Some(period),
+            ..Self::new(points)
+        }
+    }
+
+    /// Returns value at time `t`.
+    pub fn value(&self, t: f64) -> f64 {
+        let Some(period) = self.period else {
+            return self.interpolate(t);
+        };
+
+        let t = t.rem_euclid(period);
+        let (t0, v0) = self.points[self.points.len() - 1];
+        let (t1, v1) = self.points[0];
+        if t1 <= t && t <= t0 {
+            return self.interpolate(t);
+        }
+
+        let t = if t < t1 { t + period } else { t };
+        let r = (t - t0) / (t1 + period - t0);
+        v0 + (v1 - v0) * r
+    }
+
+    /// Returns value at time `t` with clamping out of points.
+    fn interpolate(&self, t: f64) -> f64 {
+        let pos = self.points.partition_point(|x| x.0 <= t);
+        if pos == 0 {
+            return self.points[0].1;
+        }
+
+        if pos == self.points.len() {
+            return self.points[pos - 1].1;
+        }
+
+        let (t0, v0) = self.points[pos - 1];
+        let (t1, v1) = self.points[pos];
+        let r = (t - t0) / (t1 - t0);
+        v0 + (v1 - v0) * r
+    }
+}
+
+mod msg {
+    pub const EMPTY_POINTS: &str = "Schedule points is empty.";
+    pub const UNSORTED: &str = "Schedule points is not sorted.";
+    pub const INVALID_PERIOD: &str = "Schedule period is not positive finite value.";
+    pub const OUT_OF_PERIOD: &str = "Schedule point time is out of period.";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net_ode::NdeqSim;
+    use crate::net_ode::solver::adapters::NetRungeKutta;
+    use crate::parts::{CompiledNet, EdgeMode, NdeqNet};
+    use std::cell::RefCell;
+
+    /// Network of two nodes with edge weight by schedule.
+    struct Scheduled(RefCell<Vec<f64>>, Schedule);
+
+    impl NdeqNet<f64, f64> for Scheduled {
+        fn edges(&self) -> Box<dyn Iterator<Item = (usize, usize, f64)> + '_> {
+            self.edges_at(0.0)
+        }
+
+        fn edges_at(&self, t: f64) -> Box<dyn Iterator<Item = (usize, usize, f64)> + '_> {
+            Box::new([(0, 1, self.1.value(t))].into_iter())
+        }
+
+        fn edge_mode(&self) -> EdgeMode {
+            EdgeMode::Undirected
+        }
+
+        fn is_time_varying(&self) -> bool {
+            true
+        }
+
+        fn import_values(&self, values: &[f64]) {
+            self.0.borrow_mut().clone_from_slice(values);
+        }
+
+        fn export_values(&self, values: &mut Vec<f64>) {
+            values.clone_from(&self.0.borrow());
+        }
+    }
+
+    #[test]
+    fn periodic_value_wraps_to_next_period() {
+        let schedule = Schedule::periodic(vec![(6.0, 1.0), (18.0, 0.0)], 24.0);
+        assert_eq!(schedule.value(0.0), 0.5);
+        assert_eq!(schedule.value(3.0), 0.75);
+        assert_eq!(schedule.value(12.0), 0.5);
+        assert_eq!(schedule.value(21.0), 0.25);
+        assert_eq!(schedule.value(-3.0), 0.25);
+        assert_eq!(schedule.value(30.0), 1.0);
+        assert!((schedule.value(24.0 - 1e-9) - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    #[should_panic(expected = "Schedule point time is out of period.")]
+    fn periodic_rejects_point_at_period() {
+        Schedule::periodic(vec![(0.0, 1.0), (24.0, 0.0)], 24.0);
+    }
+
+    #[test]
+    fn weight_change_mid_run_affects_trajectory() {
+        // Edge is opened at `t = 1`, and its weight `w` grows by `2 (t - 1)`.
+        // Then, difference of values decays by `e^(-2 ∫w dt)`, while sum of
+        // values is kept.
+        let schedule = Schedule::new(vec![(1.0, 0.0), (2.0, 2.0)]);
+        let net = Scheduled(RefCell::new(vec![1.0, 0.0]), schedule);
+        let compiled = CompiledNet::new(&net);
+        let solver = NetRungeKutta::new(0.01);
+        let expected = (1.0 + (-2.0f64).exp()) / 2.0;
+        for target in [&net as &dyn NdeqNet<f64, f64>, &compiled] {
+            net.0.replace(vec![1.0, 0.0]);
+            let mut sim = NdeqSim::new(target, &solver);
+            sim.run(1.0);
+            assert_eq!(*net.0.borrow(), [1.0, 0.0]);
+
+            sim.run(1.0);
+            let values = net.0.borrow();
+            assert!((values[0] - expected).abs() < 1e-9);
+            assert!((values[0] + values[1] - 1.0).abs() < 1e-12);
+        }
+    }
+}
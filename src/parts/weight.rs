@@ -0,0 +1,41 @@
+//! Provider of [`Weight`].
+
+use crate::ode::values::{RF32, RF64, Value};
+use std::fmt::Debug;
+use std::ops::AddAssign;
+
+/// Edge weight type.
+///
+/// This is implemented for [`f32`] and [`f64`]. Use [`f64`] if weights span
+/// many orders of magnitude.
+pub trait Weight: Copy + Debug + Default + PartialOrd + AddAssign + 'static {
+    /// Creates a weight from [`f64`] (precision may be lost).
+    fn from_f64(value: f64) -> Self;
+
+    /// Returns weight as [`f64`].
+    fn to_f64(self) -> f64;
+
+    /// Multiplies `value` by `self`.
+    fn scale<V: Value>(self, value: &mut V);
+}
+
+macro_rules! impl_weight {
+    ($ty:ty, $rf:ident) => {
+        impl Weight for $ty {
+            fn from_f64(value: f64) -> Self {
+                value as $ty
+            }
+
+            fn to_f64(self) -> f64 {
+                f64::from(self)
+            }
+
+            fn scale<V: Value>(self, value: &mut V) {
+                *value *= $rf(self);
+            }
+        }
+    };
+}
+
+impl_weight!(f32, RF32);
+impl_weight!(f64, RF64);
@@ -0,0 +1,476 @@
+//! Provider of [`Ensemble`].
+
+use crate::net_ode::NdeqSim;
+use crate::net_ode::solver::NetOdeSolver;
+use crate::parts::{EdgeMode, Laplacian, NdeqNet, Weight};
+use crate::uncertainty::{EnsembleStats, SampleRng};
+use crate::util::NetCopy;
+use std::cell::RefCell;
+use std::thread;
+
+/// Distribution closure (returns sample from generator and nominal value).
+type Dist<'a> = dyn Fn(&mut SampleRng, f64) -> f64 + Sync + 'a;
+
+/// Simulator configuration closure (called for simulator of each sample).
+type Config<'a> = dyn for<'s> Fn(&mut NdeqSim<'s, f64, f64, f64>) + Sync + 'a;
+
+/// Monte Carlo ensemble run over uncertain node values and edge weights.
+///
+/// Each sample draws node values and edge weights from distributions, and
+/// runs simulation with ODE solver. Node values and edge weights without
+/// distribution are nominal (current values of target network). Samples run
+/// in parallel on copies of target network, so target network is not rebuilt
+/// nor changed.
+///
+/// Each sample has own generator seeded from ensemble seed and sample index.
+/// So, result is same for same seed regardless of threads count.
+///
+/// Topology, edge mode, Laplacian variant, fixed nodes, capacities, constant
+/// sources and advection edges of target network are used by simulation.
+/// Other settings of simulator (like flow law, sources and reaction) are set
+/// by [`set_sim_config`](Self::set_sim_config). Time-varying edge weights are
+/// copied at stage times of ODE solver (found by simulation of nominal sample
+/// before samples run), and distribution of their edge weight shifts edge
+/// weights of all times (nominal value is edge weight at time zero).
+pub struct Ensemble<'a, W = f32> {
+    /// Target network.
+    net: &'a dyn NdeqNet<f64, W>,
+
+    /// ODE solver factory.
+    solver: &'a (dyn NetOdeSolver<f64, f64> + Sync),
+
+    /// Simulator configuration (`None` means default simulator).
+    config: Option<Box<Config<'a>>>,
+
+    /// Seed of generators.
+    seed: u64,
+
+    /// Threads count (zero means available parallelism).
+    threads: usize,
+
+    /// Distributions of node values.
+    values: Vec<(usize, Box<Dist<'a>>)>,
+
+    /// Distributions of edge weights.
+    weights: Vec<(usize, Box<Dist<'a>>)>,
+}
+
+impl<'a, W> Ensemble<'a, W>
+where
+    W: Weight,
+{
+    /// Creates a new instance.
+    ///
+    /// Seed is `0` and threads count is available parallelism by default.
+    /// `solver` is shared by threads.
+    pub fn new(
+        net: &'a dyn NdeqNet<f64, W>,
+        solver: &'a (dyn NetOdeSolver<f64, f64> + Sync),
+    ) -> Self {
+        Self {
+            net,
+            solver,
+            config: None,
+            seed: 0,
+            threads: 0,
+            values: Vec::new(),
+            weights: Vec::new(),
+        }
+    }
+
+    /// Sets seed of generators.
+    pub fn set_seed(&mut self, value: u64) {
+        self.seed = value;
+    }
+
+    /// Sets threads count (zero means available parallelism).
+    pub fn set_threads(&mut self, value: usize) {
+        self.threads = value;
+    }
+
+    /// Sets distribution of node value.
+    ///
+    /// `f` returns sample from generator and nominal value.
+    pub fn set_value_distribution<F>(&mut self, idx: usize, f: F)
+    where
+        F: Fn(&mut SampleRng, f64) -> f64 + Sync + 'a,
+    {
+        self.values.retain(|x| x.0 != idx);
+        self.values.push((idx, Box::new(f)));
+    }
+
+    /// Sets distribution of edge weight.
+    ///
+    /// `k` is index of edge in [`NdeqNet::edges`]. `f` returns sample from
+    /// generator and nominal value.
+    pub fn set_weight_distribution<F>(&mut self, k: usize, f: F)
+    where
+        F: Fn(&mut SampleRng, f64) -> f64 + Sync + 'a,
+    {
+        self.weights.retain(|x| x.0 != k);
+        self.weights.push((k, Box::new(f)));
+    }
+
+    /// Sets simulator configuration.
+    ///
+    /// `f` is called for simulator of each sample before it runs. So, it can
+    /// set flow law, sources, reaction and other settings of simulator.
+    pub fn set_sim_config<F>(&mut self, f: F)
+    where
+        F: for<'s> Fn(&mut NdeqSim<'s, f64, f64, f64>) + Sync + 'a,
+    {
+        self.config = Some(Box::new(f));
+    }
+
+    /// Removes all distributions.
+    pub fn clear_distributions(&mut self) {
+        self.values.clear();
+        self.weights.clear();
+    }
+
+    /// Runs samples and returns node values at each time.
+    ///
+    /// `times` is elapsed times from the start of simulation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the following occurs.
+    ///
+    /// * `samples` is zero.
+    /// * `times` is not sorted, or has negative or NaN time.
+    /// * Distribution has node index or edge index out of range.
+    /// * Target network is time-varying, and step size of ODE solver is not
+    ///   fixed (see [`NetOdeSolver::step_size`]).
+    /// * Target network or its nodes are currently mutably borrowed.
+    pub fn run(&self, samples: usize, times: &[f64]) -> EnsembleStats {
+        assert!(samples > 0, "{}", msg::NO_SAMPLES);
+        let mut last = 0.0;
+        for &t in times {
+            assert!(t >= last, "{}", msg::INVALID_TIMES);
+            last = t;
+        }
+
+        let mut model = NetCopy::<f64>::new(self.net);
+        if self.net.is_time_varying() {
+            self.solver.step_size().expect(msg::NO_STEP);
+            let times = self.stage_times(&model, times);
+            model.set_time_varying(self.net, &times);
+        }
+
+        let nodes = model.initial().len();
+        let edges = model.edge_nodes().len();
+        for (idx, _) in &self.values {
+            assert!(*idx < nodes, "{}", msg::IDX_OUT_OF_RANGE);
+        }
+
+        for (k, _) in &self.weights {
+            assert!(*k < edges, "{}", msg::EDGE_OUT_OF_RANGE);
+        }
+
+        let threads = match self.threads {
+            0 => thread::available_parallelism().map_or(1, |x| x.get()),
+            x => x,
+        };
+        let sampler = Sampler {
+            solver: self.solver,
+            config: self.config.as_deref(),
+            seed: self.seed,
+            values: &self.values,
+            weights: &self.weights,
+            nominal: model.weights(),
+        };
+        let sampler = &sampler;
+        let chunk = samples.div_ceil(threads.min(samples));
+        let results = thread::scope(|s| {
+            let handles = (0..samples)
+                .step_by(chunk)
+                .map(|start| {
+                    let model = model.clone();
+                    let end = (start + chunk).min(samples);
+                    s.spawn(move || {
+                        let run = |i| sampler.run(&model, i, times);
+                        (start..end).map(run).collect::<Vec<_>>()
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            let results = handles.into_iter().map(|x| x.join().unwrap());
+            results.flatten().collect::<Vec<_>>()
+        });
+
+        let mut values = vec![vec![Vec::with_capacity(samples); nodes]; times.len()];
+        for sample in results {
+            for (k, x) in sample.into_iter().enumerate() {
+                for (idx, x) in x.into_iter().enumerate() {
+                    values[k][idx].push(x);
+                }
+            }
+        }
+
+        EnsembleStats::new(times.to_vec(), values)
+    }
+
+    /// Returns stage times of ODE solver by simulation of nominal sample.
+    fn stage_times(&self, model: &NetCopy<f64>, times: &[f64]) -> Vec<f64> {
+        let probe = Probe {
+            model: model.clone(),
+            times: RefCell::new(Vec::new()),
+        };
+        let mut sim = NdeqSim::new(&probe, self.solver);
+        if let Some(config) = &self.config {
+            config(&mut sim);
+        }
+
+        for &t in times {
+            if t > sim.time() {
+                sim.run(t - sim.time());
+            }
+        }
+
+        drop(sim);
+        probe.times.into_inner()
+    }
+}
+
+/// Runner of samples (shared by threads).
+struct Sampler<'s, 'a> {
+    /// ODE solver factory.
+    solver: &'a (dyn NetOdeSolver<f64, f64> + Sync),
+
+    /// Simulator configuration.
+    config: Option<&'s Config<'a>>,
+
+    /// Seed of generators.
+    seed: u64,
+
+    /// Distributions of node values.
+    values: &'s [(usize, Box<Dist<'a>>)],
+
+    /// Distributions of edge weights.
+    weights: &'s [(usize, Box<Dist<'a>>)],
+
+    /// Nominal edge weights.
+    nominal: Vec<f64>,
+}
+
+impl Sampler<'_, '_> {
+    /// Runs sample and returns node values at each time.
+    fn run(&self, model: &NetCopy<f64>, i: usize, times: &[f64]) -> Vec<Vec<f64>> {
+        let seed = self.seed ^ (i as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        let mut rng = SampleRng::new(seed);
+        let mut values = model.initial().to_vec();
+        for (idx, f) in self.values {
+            values[*idx] = f(&mut rng, values[*idx]);
+        }
+
+        let mut weights = self.nominal.clone();
+        for (k, f) in self.weights {
+            weights[*k] = f(&mut rng, weights[*k]);
+        }
+
+        model.set_values(&values);
+        model.set_weights(&weights);
+        let mut sim = NdeqSim::new(model, self.solver);
+        if let Some(config) = self.config {
+            config(&mut sim);
+        }
+
+        let mut ret = Vec::with_capacity(times.len());
+        for &t in times {
+            if t > sim.time() {
+                sim.run(t - sim.time());
+            }
+
+            let mut values = Vec::new();
+            model.export_values(&mut values);
+            ret.push(values);
+        }
+
+        ret
+    }
+}
+
+/// Copy of network which records times of edge weights requests.
+struct Probe {
+    /// Copy of target network.
+    model: NetCopy<f64>,
+
+    /// Requested times.
+    times: RefCell<Vec<f64>>,
+}
+
+impl NdeqNet<f64, f64> for Probe {
+    fn edges(&self) -> Box<dyn Iterator<Item = (usize, usize, f64)> + '_> {
+        self.model.edges()
+    }
+
+    fn edges_at(&self, t: f64) -> Box<dyn Iterator<Item = (usize, usize, f64)> + '_> {
+        self.times.borrow_mut().push(t);
+        self.model.edges()
+    }
+
+    fn is_time_varying(&self) -> bool {
+        true
+    }
+
+    fn fixed_nodes(&self) -> Box<dyn Iterator<Item = usize> + '_> {
+        self.model.fixed_nodes()
+    }
+
+    fn capacities(&self) -> Box<dyn Iterator<Item = (usize, f64)> + '_> {
+        self.model.capacities()
+    }
+
+    fn sources(&self) -> Box<dyn Iterator<Item = (usize, f64)> + '_> {
+        self.model.sources()
+    }
+
+    fn advections(&self) -> Box<dyn Iterator<Item = (usize, usize, f64)> + '_> {
+        self.model.advections()
+    }
+
+    fn edge_mode(&self) -> EdgeMode {
+        self.model.edge_mode()
+    }
+
+    fn laplacian(&self) -> Laplacian {
+        self.model.laplacian()
+    }
+
+    fn import_values(&self, values: &[f64]) {
+        self.model.import_values(values);
+    }
+
+    fn export_values(&self, values: &mut Vec<f64>) {
+        self.model.export_values(values);
+    }
+
+    fn nodes_count(&self) -> usize {
+        self.model.nodes_count()
+    }
+}
+
+mod msg {
+    pub const NO_SAMPLES: &str = "Samples count must be positive.";
+    pub const INVALID_TIMES: &str = "Times must be sorted and non-negative.";
+    pub const IDX_OUT_OF_RANGE: &str = "Distribution node index is out of nodes count.";
+    pub const EDGE_OUT_OF_RANGE: &str = "Distribution edge index is out of edges count.";
+    pub const NO_STEP: &str = "Time-varying network requires ODE solver with fixed step size.";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net_ode::Source;
+    use crate::net_ode::solver::adapters::NetRungeKutta;
+    use crate::util::TestNet;
+
+    /// Network of two nodes with edge weight `t`.
+    struct Ramp(RefCell<Vec<f64>>);
+
+    impl NdeqNet<f64, f64> for Ramp {
+        fn edges(&self) -> Box<dyn Iterator<Item = (usize, usize, f64)> + '_> {
+            self.edges_at(0.0)
+        }
+
+        fn edges_at(&self, t: f64) -> Box<dyn Iterator<Item = (usize, usize, f64)> + '_> {
+            Box::new([(0, 1, t)].into_iter())
+        }
+
+        fn is_time_varying(&self) -> bool {
+            true
+        }
+
+        fn import_values(&self, values: &[f64]) {
+            self.0.borrow_mut().clone_from_slice(values);
+        }
+
+        fn export_values(&self, values: &mut Vec<f64>) {
+            values.clone_from(&self.0.borrow());
+        }
+    }
+
+    /// Network of two nodes with edge weight stepping from zero to one.
+    struct Step(RefCell<Vec<f64>>);
+
+    impl NdeqNet<f64, f64> for Step {
+        fn edges(&self) -> Box<dyn Iterator<Item = (usize, usize, f64)> + '_> {
+            self.edges_at(0.0)
+        }
+
+        fn edges_at(&self, t: f64) -> Box<dyn Iterator<Item = (usize, usize, f64)> + '_> {
+            let w = if t < 0.23 { 0.0 } else { 1.0 };
+            Box::new([(0, 1, w)].into_iter())
+        }
+
+        fn is_time_varying(&self) -> bool {
+            true
+        }
+
+        fn import_values(&self, values: &[f64]) {
+            self.0.borrow_mut().clone_from_slice(values);
+        }
+
+        fn export_values(&self, values: &mut Vec<f64>) {
+            values.clone_from(&self.0.borrow());
+        }
+    }
+
+    #[test]
+    fn run_is_same_for_any_threads_count() {
+        let net = TestNet::new(
+            vec![1.0, 0.0, 0.0, 0.5],
+            vec![(0, 1, 1.0), (1, 2, 2.0), (2, 3, 0.5)],
+        );
+        let solver = NetRungeKutta::new(0.01);
+        let mut ensemble = Ensemble::new(&net, &solver);
+        ensemble.set_seed(7);
+        ensemble.set_value_distribution(0, |rng, x| rng.normal(x, 0.1));
+        ensemble.set_weight_distribution(1, |rng, x| rng.range(0.5 * x, 1.5 * x));
+
+        let results = [1, 3, 8].map(|threads| {
+            ensemble.set_threads(threads);
+            ensemble.run(20, &[0.5, 1.0])
+        });
+
+        assert_eq!(results[0], results[1]);
+        assert_eq!(results[0], results[2]);
+        assert!(results[0].variance(1, 0) > 0.0);
+    }
+
+    #[test]
+    fn run_follows_time_varying_weights_and_sim_config() {
+        let net = Ramp(RefCell::new(vec![1.0, 0.0]));
+        let solver = NetRungeKutta::new(0.01);
+        let mut ensemble = Ensemble::new(&net, &solver);
+        ensemble.set_sim_config(|sim| sim.add_source(1, Source::constant(0.5)));
+        let stats = ensemble.run(1, &[1.0, 2.0]);
+
+        let mut sim = NdeqSim::new(&net, &solver);
+        sim.add_source(1, Source::constant(0.5));
+        for (k, t) in [1.0, 2.0].into_iter().enumerate() {
+            sim.run(t - sim.time());
+            for idx in 0..2 {
+                let expected = net.0.borrow()[idx];
+                assert!((stats.samples(k, idx)[0] - expected).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn run_samples_step_weights_at_stage_times() {
+        let times = [0.25, 0.5, 1.0];
+        let net = Step(RefCell::new(vec![1.0, 0.0]));
+        let solver = NetRungeKutta::new(0.1);
+        let stats = Ensemble::new(&net, &solver).run(1, &times);
+
+        let mut sim = NdeqSim::new(&net, &solver);
+        for (k, t) in times.into_iter().enumerate() {
+            sim.run(t - sim.time());
+            for idx in 0..2 {
+                let expected = net.0.borrow()[idx];
+                assert!((stats.samples(k, idx)[0] - expected).abs() < 1e-12);
+            }
+        }
+    }
+}
@@ -0,0 +1,105 @@
+//! Provider of [`EnsembleStats`].
+
+/// Result of ensemble run.
+///
+/// This holds node values of all samples at each record time, and provides
+/// per-node statistics of them.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EnsembleStats {
+    /// Record times.
+    times: Vec<f64>,
+
+    /// Sorted node values of samples (time, node and sample order).
+    values: Vec<Vec<Vec<f64>>>,
+}
+
+impl EnsembleStats {
+    /// Creates a new instance.
+    ///
+    /// `values` is node values of samples in time, node and sample order.
+    pub(crate) fn new(times: Vec<f64>, mut values: Vec<Vec<Vec<f64>>>) -> Self {
+        for x in values.iter_mut().flatten() {
+            x.sort_by(f64::total_cmp);
+        }
+
+        Self { times, values }
+    }
+
+    /// Returns record times.
+    pub fn times(&self) -> &[f64] {
+        &self.times
+    }
+
+    /// Returns samples count.
+    pub fn len(&self) -> usize {
+        self.values
+            .first()
+            .and_then(|x| x.first())
+            .map_or(0, |x| x.len())
+    }
+
+    /// Returns `true` if there are no samples.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns node values of samples in ascending order.
+    ///
+    /// `k` is index of record time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` or `idx` is out of range.
+    pub fn samples(&self, k: usize, idx: usize) -> &[f64] {
+        &self.values[k][idx]
+    }
+
+    /// Returns mean of node value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` or `idx` is out of range.
+    pub fn mean(&self, k: usize, idx: usize) -> f64 {
+        let x = self.samples(k, idx);
+        x.iter().sum::<f64>() / x.len() as f64
+    }
+
+    /// Returns unbiased sample variance of node value.
+    ///
+    /// This is NaN if samples count is less than two.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` or `idx` is out of range.
+    pub fn variance(&self, k: usize, idx: usize) -> f64 {
+        let x = self.samples(k, idx);
+        if x.len() < 2 {
+            return f64::NAN;
+        }
+
+        let mean = self.mean(k, idx);
+        let sum = x.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>();
+        sum / (x.len() - 1) as f64
+    }
+
+    /// Returns quantile of node value.
+    ///
+    /// Quantile is linearly interpolated between sorted samples (`q = 0.5`
+    /// is median).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` or `idx` is out of range, or `q` is not in `[0, 1]`.
+    pub fn quantile(&self, k: usize, idx: usize, q: f64) -> f64 {
+        assert!((0.0..=1.0).contains(&q), "{}", msg::INVALID_QUANTILE);
+        let x = self.samples(k, idx);
+        let pos = q * (x.len() - 1) as f64;
+        let i = pos.floor() as usize;
+        let j = pos.ceil() as usize;
+        x[i] + (x[j] - x[i]) * (pos - i as f64)
+    }
+}
+
+mod msg {
+    pub const INVALID_QUANTILE: &str = "Quantile must be in `[0, 1]`.";
+}
@@ -0,0 +1,9 @@
+//! Monte Carlo ensemble over uncertain inputs.
+
+pub use ensemble::*;
+pub use ensemble_stats::*;
+pub use sample_rng::*;
+
+mod ensemble;
+mod ensemble_stats;
+mod sample_rng;
@@ -0,0 +1,68 @@
+//! Provider of [`SampleRng`].
+
+/// Pseudo random number generator for ensemble samples.
+///
+/// This is [xoshiro256**] seeded by [SplitMix64]. Same seed gives same
+/// sequence on all platforms.
+///
+/// [xoshiro256**]: https://prng.di.unimi.it/
+/// [SplitMix64]: https://prng.di.unimi.it/splitmix64.c
+#[derive(Clone, Debug)]
+pub struct SampleRng {
+    /// Generator state.
+    s: [u64; 4],
+}
+
+impl SampleRng {
+    /// Creates a new instance.
+    pub fn new(seed: u64) -> Self {
+        let mut x = seed;
+        let mut next = || {
+            x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = x;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^ (z >> 31)
+        };
+
+        Self {
+            s: [next(), next(), next(), next()],
+        }
+    }
+
+    /// Returns next random integer.
+    pub fn next_u64(&mut self) -> u64 {
+        let s = &mut self.s;
+        let ret = s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = s[1] << 17;
+        s[2] ^= s[0];
+        s[3] ^= s[1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = s[3].rotate_left(45);
+        ret
+    }
+
+    /// Returns uniform random number in `[0, 1)`.
+    pub fn uniform(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Returns uniform random number in `[lower, upper)`.
+    pub fn range(&mut self, lower: f64, upper: f64) -> f64 {
+        lower + (upper - lower) * self.uniform()
+    }
+
+    /// Returns normal random number.
+    ///
+    /// This uses [Box-Muller transform].
+    ///
+    /// [Box-Muller transform]: https://en.wikipedia.org/wiki/Box%E2%80%93Muller_transform
+    pub fn normal(&mut self, mean: f64, std_dev: f64) -> f64 {
+        let u1 = 1.0 - self.uniform();
+        let u2 = self.uniform();
+        let z = (-2.0 * u1.ln()).sqrt() * (std::f64::consts::TAU * u2).cos();
+        mean + std_dev * z
+    }
+}
@@ -1,5 +1,11 @@
 //! Crate's utility.
 
+mod net_copy;
+#[cfg(test)]
+mod test_net;
 mod work_on;
 
+pub use net_copy::*;
+#[cfg(test)]
+pub use test_net::*;
 pub use work_on::*;
@@ -0,0 +1,201 @@
+//! Provider of [`NetCopy`].
+
+use crate::ode::values::Value;
+use crate::parts::{EdgeMode, Laplacian, NdeqNet, Weight};
+use std::cell::RefCell;
+
+/// Copy of network with variable edge weights and node values.
+///
+/// This copies topology, edge mode, Laplacian variant, fixed nodes,
+/// capacities, constant sources and advection edges of network. So,
+/// simulations with other edge weights and node values run without
+/// rebuilding original network. Time-varying edge weights are copied by
+/// [`set_time_varying`](Self::set_time_varying).
+#[derive(Clone)]
+pub struct NetCopy<V> {
+    /// Edges (backward and forward node indices).
+    edges: Vec<(usize, usize)>,
+
+    /// Edge weights.
+    weights: RefCell<Vec<f64>>,
+
+    /// Edge mode.
+    mode: EdgeMode,
+
+    /// Laplacian variant.
+    laplacian: Laplacian,
+
+    /// Fixed nodes.
+    fixed: Vec<usize>,
+
+    /// Capacities of nodes.
+    capacities: Vec<(usize, f64)>,
+
+    /// Sources of nodes.
+    sources: Vec<(usize, f64)>,
+
+    /// Advection edges.
+    advections: Vec<(usize, usize, f64)>,
+
+    /// Node values of original network.
+    initial: Vec<f64>,
+
+    /// Edge weights of original network at times (empty if not time-varying).
+    table: Vec<(f64, Vec<f64>)>,
+
+    /// Node values.
+    values: RefCell<Vec<V>>,
+}
+
+impl<V> NetCopy<V>
+where
+    V: Value + From<f64>,
+{
+    /// Creates a new instance.
+    ///
+    /// Edge weights and node values are same as `net`.
+    pub fn new<W: Weight>(net: &dyn NdeqNet<f64, W>) -> Self {
+        let mut initial = Vec::new();
+        net.export_values(&mut initial);
+        let ret = Self {
+            edges: net.edges().map(|(b, f, _)| (b, f)).collect(),
+            weights: RefCell::new(net.edges().map(|(_, _, w)| w.to_f64()).collect()),
+            mode: net.edge_mode(),
+            laplacian: net.laplacian(),
+            fixed: net.fixed_nodes().collect(),
+            capacities: net.capacities().collect(),
+            sources: net.sources().collect(),
+            advections: net.advections().collect(),
+            initial,
+            table: Vec::new(),
+            values: Default::default(),
+        };
+
+        ret.reset_values();
+        ret
+    }
+
+    /// Copies time-varying edge weights of network.
+    ///
+    /// Edge weights are copied at `times` (and time zero), so they are exact
+    /// at these times. Between them, edge weights are interpolated linearly,
+    /// and after the last time, edge weights at the last time are held. Edge
+    /// weights set by [`set_weights`](Self::set_weights) shift edge weights
+    /// of all times by difference from edge weights at time zero.
+    pub fn set_time_varying<W: Weight>(&mut self, net: &dyn NdeqNet<f64, W>, times: &[f64]) {
+        let mut times = [0.0].iter().chain(times).copied().collect::<Vec<_>>();
+        times.sort_by(f64::total_cmp);
+        times.dedup();
+        self.table = times
+            .into_iter()
+            .map(|t| (t, net.edges_at(t).map(|(_, _, w)| w.to_f64()).collect()))
+            .collect();
+    }
+
+    /// Returns edges (backward and forward node indices).
+    pub fn edge_nodes(&self) -> &[(usize, usize)] {
+        &self.edges
+    }
+
+    /// Returns node values of original network.
+    pub fn initial(&self) -> &[f64] {
+        &self.initial
+    }
+
+    /// Returns edge weights.
+    pub fn weights(&self) -> Vec<f64> {
+        self.weights.borrow().clone()
+    }
+
+    /// Sets edge weights.
+    pub fn set_weights(&self, weights: &[f64]) {
+        self.weights.borrow_mut().clone_from(&weights.to_vec());
+    }
+
+    /// Returns node value.
+    pub fn value(&self, idx: usize) -> V {
+        self.values.borrow()[idx].clone()
+    }
+
+    /// Sets node values.
+    pub fn set_values(&self, values: &[f64]) {
+        *self.values.borrow_mut() = values.iter().map(|&x| V::from(x)).collect();
+    }
+
+    /// Resets node values to node values of original network.
+    pub fn reset_values(&self) {
+        self.set_values(&self.initial);
+    }
+}
+
+impl<V> NdeqNet<V, f64> for NetCopy<V>
+where
+    V: Value + From<f64>,
+{
+    fn edges(&self) -> Box<dyn Iterator<Item = (usize, usize, f64)> + '_> {
+        let weights = self.weights.borrow();
+        let edges = self.edges.iter().enumerate();
+        Box::new(edges.map(move |(k, &(b, f))| (b, f, weights[k])))
+    }
+
+    fn edges_at(&self, t: f64) -> Box<dyn Iterator<Item = (usize, usize, f64)> + '_> {
+        let Some((_, w0)) = self.table.first() else {
+            return self.edges();
+        };
+
+        let idx = self.table.partition_point(|x| x.0 <= t).max(1);
+        let (t1, w1) = &self.table[idx - 1];
+        let (t2, w2) = &self.table[idx.min(self.table.len() - 1)];
+        let r = match t2 > t1 {
+            true => ((t - t1) / (t2 - t1)).clamp(0.0, 1.0),
+            false => 0.0,
+        };
+
+        let weights = self.weights.borrow();
+        let edges = self.edges.iter().enumerate();
+        Box::new(edges.map(move |(k, &(b, f))| {
+            let w = w1[k] + r * (w2[k] - w1[k]) + weights[k] - w0[k];
+            (b, f, w)
+        }))
+    }
+
+    fn is_time_varying(&self) -> bool {
+        !self.table.is_empty()
+    }
+
+    fn fixed_nodes(&self) -> Box<dyn Iterator<Item = usize> + '_> {
+        Box::new(self.fixed.iter().copied())
+    }
+
+    fn capacities(&self) -> Box<dyn Iterator<Item = (usize, f64)> + '_> {
+        Box::new(self.capacities.iter().copied())
+    }
+
+    fn sources(&self) -> Box<dyn Iterator<Item = (usize, V)> + '_> {
+        Box::new(self.sources.iter().map(|&(idx, x)| (idx, V::from(x))))
+    }
+
+    fn advections(&self) -> Box<dyn Iterator<Item = (usize, usize, f64)> + '_> {
+        Box::new(self.advections.iter().copied())
+    }
+
+    fn edge_mode(&self) -> EdgeMode {
+        self.mode
+    }
+
+    fn laplacian(&self) -> Laplacian {
+        self.laplacian
+    }
+
+    fn import_values(&self, values: &[V]) {
+        self.values.borrow_mut().clone_from(&values.to_vec());
+    }
+
+    fn export_values(&self, values: &mut Vec<V>) {
+        values.clone_from(&self.values.borrow());
+    }
+
+    fn nodes_count(&self) -> usize {
+        self.values.borrow().len()
+    }
+}
@@ -0,0 +1,156 @@
+//! Provider of [`TestNet`] and [`finite_diff`].
+
+use crate::ode::values::Value;
+use crate::parts::{EdgeMode, Laplacian, NdeqNet, Weight};
+use std::cell::RefCell;
+
+/// Node values of path fixture (see [`TestNet::path`]).
+pub const PATH_VALUES: [f64; 3] = [1.0, 0.0, 0.5];
+
+/// Edge weights of path fixture (see [`TestNet::path`]).
+pub const PATH_WEIGHTS: [f64; 2] = [2.0, 0.5];
+
+/// Network with plain fields for unit tests.
+pub struct TestNet<V, W = f64> {
+    /// Edges.
+    pub edges: Vec<(usize, usize, W)>,
+
+    /// Edge mode.
+    pub mode: EdgeMode,
+
+    /// Laplacian variant.
+    pub laplacian: Laplacian,
+
+    /// Fixed nodes.
+    pub fixed: RefCell<Vec<usize>>,
+
+    /// Capacities of nodes.
+    pub capacities: Vec<(usize, f64)>,
+
+    /// Advection edges.
+    pub advections: Vec<(usize, usize, f64)>,
+
+    /// Node values.
+    pub values: RefCell<Vec<V>>,
+
+    /// Stable node ids (empty means no ids).
+    pub ids: RefCell<Vec<usize>>,
+}
+
+impl<V, W> TestNet<V, W> {
+    /// Creates a new instance with undirected edges.
+    pub fn new(values: Vec<V>, edges: Vec<(usize, usize, W)>) -> Self {
+        Self {
+            edges,
+            mode: EdgeMode::Undirected,
+            laplacian: Laplacian::Combinatorial,
+            fixed: Default::default(),
+            capacities: Vec::new(),
+            advections: Vec::new(),
+            values: RefCell::new(values),
+            ids: Default::default(),
+        }
+    }
+
+    /// Returns node values.
+    pub fn values(&self) -> Vec<V>
+    where
+        V: Clone,
+    {
+        self.values.borrow().clone()
+    }
+}
+
+impl TestNet<f64> {
+    /// Returns networks of four nodes for each edge mode and Laplacian variant.
+    ///
+    /// Networks have cyclic and dangling edges with various weights, and
+    /// capacities of some nodes.
+    pub fn variants() -> impl Iterator<Item = Self> {
+        let modes = [EdgeMode::Undirected, EdgeMode::InFlow, EdgeMode::OutFlow];
+        let laplacians = [
+            Laplacian::Combinatorial,
+            Laplacian::RandomWalk,
+            Laplacian::SymmetricNormalized,
+        ];
+
+        let variants = modes
+            .into_iter()
+            .flat_map(move |m| laplacians.map(|l| (m, l)));
+        variants.map(|(mode, laplacian)| {
+            let values = vec![1.0, 4.0, 0.5, 2.0];
+            let edges = vec![(0, 1, 0.5), (1, 2, 2.0), (2, 0, 1.5), (3, 1, 0.25)];
+            let mut ret = Self::new(values, edges);
+            ret.mode = mode;
+            ret.laplacian = laplacian;
+            ret.capacities = vec![(1, 2.0), (3, 0.5)];
+            ret
+        })
+    }
+}
+
+impl<V> TestNet<V> {
+    /// Creates a new instance of path of three nodes with edge weights `w`.
+    ///
+    /// This is fixture of derivative tests (with [`PATH_VALUES`] and
+    /// [`PATH_WEIGHTS`]).
+    pub fn path(values: Vec<V>, w: [f64; 2]) -> Self {
+        Self::new(values, vec![(0, 1, w[0]), (1, 2, w[1])])
+    }
+}
+
+impl<V, W> NdeqNet<V, W> for TestNet<V, W>
+where
+    V: Value,
+    W: Weight,
+{
+    fn edges(&self) -> Box<dyn Iterator<Item = (usize, usize, W)> + '_> {
+        Box::new(self.edges.iter().copied())
+    }
+
+    fn fixed_nodes(&self) -> Box<dyn Iterator<Item = usize> + '_> {
+        Box::new(self.fixed.borrow().clone().into_iter())
+    }
+
+    fn capacities(&self) -> Box<dyn Iterator<Item = (usize, f64)> + '_> {
+        Box::new(self.capacities.iter().copied())
+    }
+
+    fn advections(&self) -> Box<dyn Iterator<Item = (usize, usize, f64)> + '_> {
+        Box::new(self.advections.iter().copied())
+    }
+
+    fn edge_mode(&self) -> EdgeMode {
+        self.mode
+    }
+
+    fn laplacian(&self) -> Laplacian {
+        self.laplacian
+    }
+
+    fn import_values(&self, values: &[V]) {
+        self.values.borrow_mut().clone_from(&values.to_vec());
+    }
+
+    fn export_values(&self, values: &mut Vec<V>) {
+        values.clone_from(&self.values.borrow());
+    }
+
+    fn export_ids(&self, ids: &mut Vec<usize>) {
+        ids.clone_from(&self.ids.borrow());
+    }
+}
+
+/// Returns derivatives of `f` by component `k` of `x` by central finite differences.
+pub fn finite_diff<const N: usize>(
+    f: impl Fn([f64; N]) -> Vec<f64>,
+    x: [f64; N],
+    k: usize,
+) -> Vec<f64> {
+    let e = 1e-6;
+    let [mut lo, mut hi] = [x; 2];
+    lo[k] -= e;
+    hi[k] += e;
+    let pairs = f(lo).into_iter().zip(f(hi));
+    pairs.map(|(lo, hi)| (hi - lo) / (2.0 * e)).collect()
+}